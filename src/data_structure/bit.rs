@@ -1,69 +1,87 @@
 #![allow(dead_code)]
 use cargo_snippet::snippet;
-use num::traits::Zero;
-use std::ops::*;
+use crate::data_structure::monoid::Group;
 
 
-#[snippet("bit")]
-#[derive(Debug)]
-struct BIT<T>
+#[snippet("bit", include = "monoid")]
+/// Binary Indexed Tree over an abelian group (e.g. `Sum`, `Xor`): point add and range sum.
+/// All ranges are half-open `[l, r)`.
+pub struct BIT<G>
 where
-    T: Clone + Copy + Zero + 
-       Add<Output=T> + Sub<Output=T> + 
-       AddAssign + SubAssign +
-       PartialEq + PartialOrd + std::fmt::Debug
+    G: Group,
 {
     n: usize,
-    data: Vec<T>,
+    data: Vec<G::S>,
+    g: G,
 }
 
-#[snippet("bit")]
-impl<T> BIT<T>
+#[snippet("bit", include = "monoid")]
+impl<G> std::fmt::Debug for BIT<G>
 where
-    T: Clone + Copy + Zero + 
-       Add<Output=T> + Sub<Output=T> + 
-       AddAssign + SubAssign +
-       PartialEq + PartialOrd + std::fmt::Debug
+    G: Group,
+    G::S: std::fmt::Debug,
 {
-    pub fn new(n: usize) -> Self {
-        let data: Vec<T> = vec![T::zero(); n+1];
-        Self { n, data }
-    }
-
-    pub fn add(&mut self, i: usize, val: T) {
-        let i = i + 1;
-        if i == 0 { return }
-        let mut k = i as i128;
-        while k <= self.n as i128 {
-            self.data[k as usize] += val;
-            k += k & -k;
-        }
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "BIT {{ n: {}, data: {:?} }}", self.n, self.data)
     }
+}
 
-    // Calc a[l]+a[l+1]+...a[r]
-    pub fn sum(&self, l: isize, r: isize) -> T {
-        return self.sum_subtask(r) - self.sum_subtask(l-1);
+#[snippet("bit", include = "monoid")]
+impl<G> BIT<G>
+where
+    G: Group,
+{
+    pub fn new(n: usize, g: G) -> Self {
+        let data = vec![g.identity(); n+1];
+        Self { n, data, g }
     }
 
-    // Calc a[0] + a[1] + ... + a[i]
-    fn sum_subtask(&self, i: isize) -> T {
-        let i = i + 1;
-        let mut s: T = T::zero();
-        if i == 0 {
-            s
-        } else {
-            let mut k = i as i128;
-            while k > 0 {
-                s += self.data[k as usize];
-                k -= k & -k;
-            }
-            s
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// `a[i] += val`
+    pub fn add(&mut self, i: usize, val: G::S) {
+        assert!(i < self.n);
+        let mut k = i + 1;
+        while k <= self.n {
+            self.data[k] = self.g.op(&self.data[k], &val);
+            k += k & k.wrapping_neg();
         }
     }
 
-    // Found minimum i where a[0] + a[1] + ... + a[i] >= x (forall a[k] >= 0)
-    pub fn lower_bound(&self, x: T) -> usize {
-        if x <= T::zero() {
+    /// Calc a[0] + a[1] + ... + a[i-1]
+    pub fn prefix_sum(&self, i: usize) -> G::S {
+        assert!(i <= self.n);
+        let mut s = self.g.identity();
+        let mut k = i;
+        while k > 0 {
+            s = self.g.op(&s, &self.data[k]);
+            k -= k & k.wrapping_neg();
+        }
+        s
+    }
+
+    /// Calc a[l] + a[l+1] + ... + a[r-1]
+    pub fn sum(&self, l: usize, r: usize) -> G::S {
+        assert!(l <= r && r <= self.n);
+        self.g.inv_op(&self.prefix_sum(r), &self.prefix_sum(l))
+    }
+}
+
+#[snippet("bit", include = "monoid")]
+impl<G> BIT<G>
+where
+    G: Group,
+    G::S: PartialOrd,
+{
+    /// Minimum i where a[0] + a[1] + ... + a[i] >= x (forall a[k] >= 0), or `n` if there is none
+    pub fn lower_bound(&self, x: G::S) -> usize {
+        if x <= self.g.identity() {
             0
         } else {
             let mut x = x;
@@ -72,8 +90,8 @@ where
             while r < self.n { r <<= 1; }
             let mut len = r;
             while len > 0 {
-                if i + len < self.n && self.data[i+len] < x {
-                    x -= self.data[i+len];
+                if i + len <= self.n && self.data[i+len] < x {
+                    x = self.g.inv_op(&x, &self.data[i+len]);
                     i += len;
                 }
                 len >>= 1;
@@ -83,30 +101,145 @@ where
     }
 }
 
+#[snippet("bit", include = "monoid")]
+// `x + x + ... + x` (`k` times) by doubling
+fn group_times<G: Group>(g: &G, x: &G::S, k: usize) -> G::S {
+    let (mut res, mut b, mut k) = (g.identity(), x.clone(), k);
+    while k > 0 {
+        if k & 1 == 1 { res = g.op(&res, &b); }
+        b = g.op(&b, &b);
+        k >>= 1;
+    }
+    res
+}
+
+#[snippet("bit", include = "monoid")]
+/// Range add and range sum with two BITs.
+///
+/// `a[0] + ... + a[i-1] = b1.prefix_sum(i) * i + b0.prefix_sum(i)`
+pub struct RangeAddBIT<G>
+where
+    G: Group,
+{
+    b0: BIT<G>,
+    b1: BIT<G>,
+}
+
+#[snippet("bit", include = "monoid")]
+impl<G> RangeAddBIT<G>
+where
+    G: Group + Clone,
+{
+    pub fn new(n: usize, g: G) -> Self {
+        Self { b0: BIT::new(n+1, g.clone()), b1: BIT::new(n+1, g) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.b0.n - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `a[i] += x` for `l <= i < r`
+    pub fn add(&mut self, l: usize, r: usize, x: G::S) {
+        assert!(l <= r && r <= self.len());
+        let g = &self.b0.g;
+        let neg = g.inv_op(&g.identity(), &x);
+        let xl = group_times(g, &x, l);
+        let xr = group_times(g, &x, r);
+        let neg_xl = g.inv_op(&g.identity(), &xl);
+        self.b0.add(l, neg_xl);
+        self.b0.add(r, xr);
+        self.b1.add(l, x);
+        self.b1.add(r, neg);
+    }
+
+    /// Calc a[0] + a[1] + ... + a[i-1]
+    pub fn prefix_sum(&self, i: usize) -> G::S {
+        assert!(i <= self.len());
+        let g = &self.b0.g;
+        let s1 = group_times(g, &self.b1.prefix_sum(i), i);
+        g.op(&s1, &self.b0.prefix_sum(i))
+    }
+
+    /// Calc a[l] + a[l+1] + ... + a[r-1]
+    pub fn sum(&self, l: usize, r: usize) -> G::S {
+        assert!(l <= r && r <= self.len());
+        self.b0.g.inv_op(&self.prefix_sum(r), &self.prefix_sum(l))
+    }
+}
+
+#[snippet("bit", include = "monoid")]
+/// Range add and point get: a BIT over the differences `a[i] - a[i-1]`.
+pub struct RangeAddPointGetBIT<G>
+where
+    G: Group,
+{
+    bit: BIT<G>,
+}
+
+#[snippet("bit", include = "monoid")]
+impl<G> RangeAddPointGetBIT<G>
+where
+    G: Group,
+{
+    pub fn new(n: usize, g: G) -> Self {
+        Self { bit: BIT::new(n+1, g) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bit.n - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `a[i] += x` for `l <= i < r`
+    pub fn add(&mut self, l: usize, r: usize, x: G::S) {
+        assert!(l <= r && r <= self.len());
+        let neg = self.bit.g.inv_op(&self.bit.g.identity(), &x);
+        self.bit.add(l, x);
+        self.bit.add(r, neg);
+    }
+
+    pub fn get(&self, i: usize) -> G::S {
+        assert!(i < self.len());
+        self.bit.prefix_sum(i + 1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data_structure::monoid::*;
+    use crate::math::modint::modint::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
 
     #[test]
     fn test_small_query() {
-        let mut bit = BIT::<i64>::new(8);
+        let mut bit = BIT::<Sum<i64>>::new(8, Sum::new());
         bit.add(1, 4);
         bit.add(0, 1);
         bit.add(5, 4);
         bit.add(5, 1);
         bit.add(2, 7);
         bit.add(6, -1);
-        assert_eq!(bit.sum(0, 7), 16);
-        assert_eq!(bit.sum(4, 7), 4);
-        assert_eq!(bit.sum(0, 1), 5);
+        assert_eq!(bit.sum(0, 8), 16);
+        assert_eq!(bit.sum(4, 8), 4);
+        assert_eq!(bit.sum(0, 2), 5);
         bit.add(7, -10);
-        assert_eq!(bit.sum(0, 7), 6);
-        assert_eq!(bit.sum(6, 7), -11);
+        assert_eq!(bit.sum(0, 8), 6);
+        assert_eq!(bit.sum(6, 8), -11);
+        assert_eq!(bit.sum(3, 3), 0);
     }
 
     #[test]
     fn test_small_bitsearch() {
-        let mut bit = BIT::<i64>::new(8);
+        let mut bit = BIT::new(8, Sum::new());
         // [1, 2, 3, 4, 5, 6, 7, 8]
         for i in 0..8 {
             bit.add(i, (i+1) as i64);
@@ -114,5 +247,50 @@ mod tests {
         assert_eq!(bit.lower_bound(11), 4);
         assert_eq!(bit.lower_bound(10), 3);
         assert_eq!(bit.lower_bound(9), 3);
+        assert_eq!(bit.lower_bound(36), 7);
+        assert_eq!(bit.lower_bound(37), 8);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_xor_and_modint() {
+        let a = [3u32, 5, 6, 9, 12];
+        let mut bit = BIT::new(5, Xor::new());
+        for (i, &x) in a.iter().enumerate() {
+            bit.add(i, x);
+        }
+        assert_eq!(bit.sum(1, 4), 5 ^ 6 ^ 9);
+        assert_eq!(bit.sum(0, 5), a.iter().fold(0, |s, x| s ^ x));
+
+        set_modint(7i64);
+        let mut bit = BIT::new(4, Sum::new());
+        for i in 0..4 {
+            bit.add(i, ModInt::new(5i64));
+        }
+        // 5 * 3 = 15 = 1 (mod 7)
+        assert_eq!(bit.sum(1, 4).value(), 1);
+    }
+
+    #[test]
+    fn test_range_add_variants() {
+        let mut rng = rand::thread_rng();
+        for n in 1..20 {
+            let mut a = vec![0i64; n];
+            let mut rs = RangeAddBIT::new(n, Sum::new());
+            let mut pg = RangeAddPointGetBIT::new(n, Sum::new());
+            for _ in 0..100 {
+                let l = rng.sample(Uniform::from(0..=n));
+                let r = rng.sample(Uniform::from(l..=n));
+                let x = rng.sample(Uniform::from(-100..100));
+                rs.add(l, r, x);
+                pg.add(l, r, x);
+                a[l..r].iter_mut().for_each(|v| *v += x);
+
+                let l = rng.sample(Uniform::from(0..=n));
+                let r = rng.sample(Uniform::from(l..=n));
+                assert_eq!(rs.sum(l, r), a[l..r].iter().sum::<i64>());
+                let i = rng.sample(Uniform::from(0..n));
+                assert_eq!(pg.get(i), a[i]);
+            }
+        }
+    }
+}
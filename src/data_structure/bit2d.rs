@@ -0,0 +1,248 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::Group;
+
+
+#[snippet("bit2d", include = "monoid")]
+/// 2D Binary Indexed Tree over an `h x w` grid and an abelian group (e.g. `Sum`, `Xor`):
+/// point add and rectangle sum in `O(log h log w)`.
+pub struct BIT2D<G>
+where
+    G: Group,
+{
+    h: usize,
+    w: usize,
+    data: Vec<Vec<G::S>>,
+    g: G,
+}
+
+#[snippet("bit2d", include = "monoid")]
+impl<G> std::fmt::Debug for BIT2D<G>
+where
+    G: Group,
+    G::S: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "BIT2D {{ h: {}, w: {}, data: {:?} }}", self.h, self.w, self.data)
+    }
+}
+
+#[snippet("bit2d", include = "monoid")]
+impl<G> BIT2D<G>
+where
+    G: Group,
+{
+    pub fn new(h: usize, w: usize, g: G) -> Self {
+        let data = vec![vec![g.identity(); w+1]; h+1];
+        Self { h, w, data, g }
+    }
+
+    /// `a[i][j] += val`
+    pub fn add(&mut self, i: usize, j: usize, val: G::S) {
+        assert!(i < self.h && j < self.w);
+        let mut x = i + 1;
+        while x <= self.h {
+            let mut y = j + 1;
+            while y <= self.w {
+                self.data[x][y] = self.g.op(&self.data[x][y], &val);
+                y += y & y.wrapping_neg();
+            }
+            x += x & x.wrapping_neg();
+        }
+    }
+
+    /// Sum of `a[x][y]` for `x < i`, `y < j`
+    pub fn prefix_sum(&self, i: usize, j: usize) -> G::S {
+        assert!(i <= self.h && j <= self.w);
+        let mut s = self.g.identity();
+        let mut x = i;
+        while x > 0 {
+            let mut y = j;
+            while y > 0 {
+                s = self.g.op(&s, &self.data[x][y]);
+                y -= y & y.wrapping_neg();
+            }
+            x -= x & x.wrapping_neg();
+        }
+        s
+    }
+
+    /// Sum of `a[x][y]` for `i1 <= x < i2`, `j1 <= y < j2`
+    pub fn sum(&self, i1: usize, j1: usize, i2: usize, j2: usize) -> G::S {
+        assert!(i1 <= i2 && j1 <= j2);
+        let plus = self.g.op(&self.prefix_sum(i2, j2), &self.prefix_sum(i1, j1));
+        let minus = self.g.op(&self.prefix_sum(i1, j2), &self.prefix_sum(i2, j1));
+        self.g.inv_op(&plus, &minus)
+    }
+}
+
+#[snippet("offline_bit2d", include = "monoid")]
+/// BIT of sorted vectors for point add / rectangle sum on sparse points with huge coordinates.
+///
+/// All points that will ever be updated must be given to `new` (offline).
+/// `O(n log n)` memory, `O(log^2 n)` per operation.
+pub struct OfflineBIT2D<G>
+where
+    G: Group,
+{
+    xs: Vec<i64>,
+    ys: Vec<Vec<i64>>,
+    data: Vec<Vec<G::S>>,
+    g: G,
+}
+
+#[snippet("offline_bit2d", include = "monoid")]
+impl<G> std::fmt::Debug for OfflineBIT2D<G>
+where
+    G: Group,
+    G::S: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "OfflineBIT2D {{ xs: {:?}, ys: {:?}, data: {:?} }}", self.xs, self.ys, self.data)
+    }
+}
+
+#[snippet("offline_bit2d", include = "monoid")]
+impl<G> OfflineBIT2D<G>
+where
+    G: Group,
+{
+    pub fn new(points: &[(i64, i64)], g: G) -> Self {
+        let mut xs: Vec<i64> = points.iter().map(|p| p.0).collect();
+        xs.sort();
+        xs.dedup();
+        let n = xs.len();
+        let mut ys = vec![vec![]; n+1];
+        for &(x, y) in points {
+            let mut i = xs.binary_search(&x).unwrap() + 1;
+            while i <= n {
+                ys[i].push(y);
+                i += i & i.wrapping_neg();
+            }
+        }
+        for v in ys.iter_mut() {
+            v.sort();
+            v.dedup();
+        }
+        let data = ys.iter().map(|v| vec![g.identity(); v.len()+1]).collect();
+        Self { xs, ys, data, g }
+    }
+
+    /// Adds `val` at `(x, y)`, which must be one of the points given to `new`.
+    pub fn add(&mut self, x: i64, y: i64, val: G::S) {
+        let n = self.xs.len();
+        let mut i = self.xs.binary_search(&x).expect("unknown point") + 1;
+        while i <= n {
+            let m = self.ys[i].len();
+            let mut j = self.ys[i].binary_search(&y).expect("unknown point") + 1;
+            while j <= m {
+                self.data[i][j] = self.g.op(&self.data[i][j], &val);
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum over points with `px < x`, `py < y`
+    pub fn prefix_sum(&self, x: i64, y: i64) -> G::S {
+        let mut s = self.g.identity();
+        let mut i = self.xs.partition_point(|&px| px < x);
+        while i > 0 {
+            let mut j = self.ys[i].partition_point(|&py| py < y);
+            while j > 0 {
+                s = self.g.op(&s, &self.data[i][j]);
+                j -= j & j.wrapping_neg();
+            }
+            i -= i & i.wrapping_neg();
+        }
+        s
+    }
+
+    /// Sum over points with `x1 <= px < x2`, `y1 <= py < y2`
+    pub fn sum(&self, x1: i64, y1: i64, x2: i64, y2: i64) -> G::S {
+        if x1 >= x2 || y1 >= y2 { return self.g.identity() }
+        let plus = self.g.op(&self.prefix_sum(x2, y2), &self.prefix_sum(x1, y1));
+        let minus = self.g.op(&self.prefix_sum(x1, y2), &self.prefix_sum(x2, y1));
+        self.g.inv_op(&plus, &minus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_bit2d_random() {
+        let mut rng = rand::thread_rng();
+        let (h, w) = (13, 7);
+        let mut a = vec![vec![0i64; w]; h];
+        let mut bit = BIT2D::new(h, w, Sum::new());
+        for _ in 0..500 {
+            let (i, j) = (rng.sample(Uniform::from(0..h)), rng.sample(Uniform::from(0..w)));
+            let x = rng.sample(Uniform::from(-100..100));
+            bit.add(i, j, x);
+            a[i][j] += x;
+
+            let i1 = rng.sample(Uniform::from(0..=h));
+            let i2 = rng.sample(Uniform::from(i1..=h));
+            let j1 = rng.sample(Uniform::from(0..=w));
+            let j2 = rng.sample(Uniform::from(j1..=w));
+            let s: i64 = a[i1..i2].iter().map(|row| row[j1..j2].iter().sum::<i64>()).sum();
+            assert_eq!(bit.sum(i1, j1, i2, j2), s);
+        }
+    }
+
+    #[test]
+    fn test_offline_bit2d_rectangle_count() {
+        let mut rng = rand::thread_rng();
+        let coord = Uniform::from(-1_000_000_000..=1_000_000_000i64);
+        let mut points: Vec<(i64, i64)> = (0..200).map(|_| (rng.sample(coord), rng.sample(coord))).collect();
+        // a few points sharing coordinates
+        points.push((points[0].0, 5));
+        points.push((7, points[1].1));
+        let mut bit = OfflineBIT2D::new(&points, Sum::new());
+        let mut w = vec![0i64; points.len()];
+        for _ in 0..300 {
+            let k = rng.sample(Uniform::from(0..points.len()));
+            let (x, y) = points[k];
+            bit.add(x, y, 1);
+            w[k] += 1;
+
+            let (x1, x2) = (rng.sample(coord), rng.sample(coord));
+            let (y1, y2) = (rng.sample(coord), rng.sample(coord));
+            let (x1, x2) = (x1.min(x2), x1.max(x2));
+            let (y1, y2) = (y1.min(y2), y1.max(y2));
+            let s: i64 = points.iter().zip(&w)
+                .filter(|(&(x, y), _)| x1 <= x && x < x2 && y1 <= y && y < y2)
+                .map(|(_, &c)| c)
+                .sum();
+            assert_eq!(bit.sum(x1, y1, x2, y2), s);
+        }
+        let total: i64 = w.iter().sum();
+        assert_eq!(bit.sum(i64::MIN, i64::MIN, i64::MAX, i64::MAX), total);
+    }
+
+    #[test]
+    fn test_bit2d_xor() {
+        let mut rng = rand::thread_rng();
+        let (h, w) = (9, 11);
+        let mut a = vec![vec![0u32; w]; h];
+        let mut bit = BIT2D::new(h, w, Xor::new());
+        for _ in 0..500 {
+            let (i, j) = (rng.sample(Uniform::from(0..h)), rng.sample(Uniform::from(0..w)));
+            let x = rng.sample(Uniform::from(0..1u32 << 20));
+            bit.add(i, j, x);
+            a[i][j] ^= x;
+
+            let i1 = rng.sample(Uniform::from(0..=h));
+            let i2 = rng.sample(Uniform::from(i1..=h));
+            let j1 = rng.sample(Uniform::from(0..=w));
+            let j2 = rng.sample(Uniform::from(j1..=w));
+            let s = a[i1..i2].iter().flat_map(|row| &row[j1..j2]).fold(0, |s, &x| s ^ x);
+            assert_eq!(bit.sum(i1, j1, i2, j2), s);
+        }
+    }
+}
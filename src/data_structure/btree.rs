@@ -1,125 +1,298 @@
 #![allow(dead_code)]
 use cargo_snippet::snippet;
 
-use std::cmp::Ordering;
-
-// Rustのコードの練習用
-// 平衡じゃないし削除もないのでいつ使うかわからん
 
 #[snippet("btree")]
-struct Node<T>
+/// Ordered set / multiset on a treap: every operation is expected `O(log n)`.
+///
+/// `new()` makes a set (`insert` ignores duplicates), `new_multi()` a multiset.
+/// Nodes live in an arena and the slots of removed nodes are reused.
+pub struct BTree<T>
 where
-    T: Ord + Copy
+    T: Ord + Clone
 {
-    value: T,
-    left: Box<BTree<T>>,
-    right: Box<BTree<T>>,
+    root: usize,
+    value: Vec<T>,
+    priority: Vec<u64>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    size: Vec<usize>,
+    free: Vec<usize>,
+    multi: bool,
+    seed: u64,
 }
 
 #[snippet("btree")]
-impl<T> Node<T>
+impl<T> Default for BTree<T>
 where
-    T: Ord + Copy
+    T: Ord + Clone
 {
-    fn new(value: T) -> Self {
-        Self {
-            value: value,
-            left: Box::new(BTree::Empty),
-            right: Box::new(BTree::Empty)
-        }
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-#[snippet("btree")]
-enum BTree<T>
-where
-    T: Ord + Copy
-{
-    Leaf(Node<T>),
-    Empty,
-}
-
 #[snippet("btree")]
 impl<T> BTree<T>
 where
-    T: Ord + Copy
+    T: Ord + Clone
 {
-    fn new() -> Self {
-        BTree::Empty
+    const NIL: usize = usize::MAX;
+
+    pub fn new() -> Self {
+        Self {
+            root: Self::NIL,
+            value: vec![],
+            priority: vec![],
+            left: vec![],
+            right: vec![],
+            size: vec![],
+            free: vec![],
+            multi: false,
+            seed: 88172645463325252,
+        }
     }
 
-    fn insert(&mut self, value: T) -> bool {
-        match self {
-            &mut BTree::Leaf(ref mut node) => {
-                match value.cmp(&node.value) {
-                    Ordering::Less    => { node.right.insert(value); }
-                    Ordering::Greater => { node.left.insert(value); }
-                    Ordering::Equal   => return false,
-                }
-            },
-            &mut BTree::Empty => {
-                *self = BTree::Leaf(Node::new(value))
-            },
-        };
+    pub fn new_multi() -> Self {
+        Self { multi: true, ..Self::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == Self::NIL
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == Self::NIL { 0 } else { self.size[t] }
+    }
+
+    fn update(&mut self, t: usize) {
+        self.size[t] = 1 + self.size(self.left[t]) + self.size(self.right[t]);
+    }
+
+    fn new_node(&mut self, value: T) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let p = self.seed;
+        if let Some(t) = self.free.pop() {
+            self.value[t] = value;
+            self.priority[t] = p;
+            self.left[t] = Self::NIL;
+            self.right[t] = Self::NIL;
+            self.size[t] = 1;
+            t
+        } else {
+            self.value.push(value);
+            self.priority.push(p);
+            self.left.push(Self::NIL);
+            self.right.push(Self::NIL);
+            self.size.push(1);
+            self.value.len() - 1
+        }
+    }
+
+    // Splits `t` into (values with `f(v)`, the rest); `f` must be true on a prefix.
+    fn split<F: Fn(&T) -> bool>(&mut self, t: usize, f: &F) -> (usize, usize) {
+        if t == Self::NIL { return (Self::NIL, Self::NIL) }
+        if f(&self.value[t]) {
+            let (a, b) = self.split(self.right[t], f);
+            self.right[t] = a;
+            self.update(t);
+            (t, b)
+        } else {
+            let (a, b) = self.split(self.left[t], f);
+            self.left[t] = b;
+            self.update(t);
+            (a, t)
+        }
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == Self::NIL { return b }
+        if b == Self::NIL { return a }
+        if self.priority[a] > self.priority[b] {
+            self.right[a] = self.merge(self.right[a], b);
+            self.update(a);
+            a
+        } else {
+            self.left[b] = self.merge(a, self.left[b]);
+            self.update(b);
+            b
+        }
+    }
+
+    /// Returns `false` (and does nothing) if `value` is already in a set.
+    pub fn insert(&mut self, value: T) -> bool {
+        if !self.multi && self.find(&value) {
+            return false;
+        }
+        let (l, r) = self.split(self.root, &|v| *v < value);
+        let t = self.new_node(value);
+        let l = self.merge(l, t);
+        self.root = self.merge(l, r);
         true
     }
 
-    fn is_empty(&self) -> bool {
-        match self {
-            &BTree::Empty    => true,
-            &BTree::Leaf(..) => false,
+    /// Removes one occurrence of `value`; returns `false` if there was none.
+    pub fn remove(&mut self, value: &T) -> bool {
+        if !self.find(value) {
+            return false;
         }
+        let (l, r) = self.split(self.root, &|v| v < value);
+        let (m, r) = self.split(r, &|v| v <= value);
+        let m2 = self.merge(self.left[m], self.right[m]);
+        self.free.push(m);
+        let l = self.merge(l, m2);
+        self.root = self.merge(l, r);
+        true
     }
 
-    fn find(&self, value: T) -> bool {
-        match self {
-            &BTree::Leaf(ref node) => {
-                match value.cmp(&node.value) {
-                    Ordering::Less    => { node.right.find(value) },
-                    Ordering::Greater => { node.left.find(value) },
-                    Ordering::Equal   => return true,
-                }
-            },
-            &BTree::Empty => false,
+    pub fn find(&self, value: &T) -> bool {
+        let mut t = self.root;
+        while t != Self::NIL {
+            if *value < self.value[t] {
+                t = self.left[t];
+            } else if self.value[t] < *value {
+                t = self.right[t];
+            } else {
+                return true;
+            }
         }
+        false
     }
 
-    fn first(&self) -> Option<T> {
-        match self {
-            &BTree::Leaf(ref node) => {
-                let right = &*node.right;
-                match right.first() {
-                    None => match right {
-                        BTree::Leaf(ref node) => Some(node.value),
-                        BTree::Empty => None,
-                    },
-                    Some(_) => unreachable!(),
-                }
-            },
-            &BTree::Empty => None,
+    pub fn count(&self, value: &T) -> usize {
+        self.rank_by(|v| v <= value) - self.rank(value)
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.kth(0)
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.len().checked_sub(1).and_then(|k| self.kth(k))
+    }
+
+    /// The `k`-th (0-indexed) smallest element
+    pub fn kth(&self, k: usize) -> Option<T> {
+        if k >= self.len() { return None }
+        let (mut t, mut k) = (self.root, k);
+        loop {
+            let ls = self.size(self.left[t]);
+            if k < ls {
+                t = self.left[t];
+            } else if k == ls {
+                return Some(self.value[t].clone());
+            } else {
+                k -= ls + 1;
+                t = self.right[t];
+            }
         }
     }
 
-    fn last(&self) -> Option<T> {
-        match self {
-            &BTree::Leaf(ref node) => {
-                let left = &*node.left;
-                match left.first() {
-                    None => match left {
-                        BTree::Leaf(ref node) => Some(node.value),
-                        BTree::Empty          => None,
-                    },
-                    Some(_) => unreachable!(),
-                }
-            },
-            &BTree::Empty => None,
+    // Number of elements `v` with `f(v)`; `f` must be true on a prefix.
+    fn rank_by<F: Fn(&T) -> bool>(&self, f: F) -> usize {
+        let (mut t, mut res) = (self.root, 0);
+        while t != Self::NIL {
+            if f(&self.value[t]) {
+                res += self.size(self.left[t]) + 1;
+                t = self.right[t];
+            } else {
+                t = self.left[t];
+            }
+        }
+        res
+    }
+
+    /// Number of elements `< value`
+    pub fn rank(&self, value: &T) -> usize {
+        self.rank_by(|v| v < value)
+    }
+
+    /// Smallest element `>= value`
+    pub fn lower_bound(&self, value: &T) -> Option<T> {
+        self.kth(self.rank(value))
+    }
+
+    /// Smallest element `> value`
+    pub fn upper_bound(&self, value: &T) -> Option<T> {
+        self.kth(self.rank_by(|v| v <= value))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.range(..)
+    }
+
+    /// In-order iterator over the elements in `range`.
+    pub fn range<R: std::ops::RangeBounds<T>>(&self, range: R) -> Iter<'_, T> {
+        let mut stack = vec![];
+        let mut t = self.root;
+        while t != Self::NIL {
+            let v = &self.value[t];
+            let after_start = match range.start_bound() {
+                std::ops::Bound::Included(s) => s <= v,
+                std::ops::Bound::Excluded(s) => s < v,
+                std::ops::Bound::Unbounded => true,
+            };
+            if after_start {
+                stack.push(t);
+                t = self.left[t];
+            } else {
+                t = self.right[t];
+            }
+        }
+        Iter { tree: self, stack, end: range.end_bound().cloned() }
+    }
+}
+
+#[snippet("btree")]
+pub struct Iter<'a, T>
+where
+    T: Ord + Clone
+{
+    tree: &'a BTree<T>,
+    stack: Vec<usize>,
+    end: std::ops::Bound<T>,
+}
+
+#[snippet("btree")]
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: Ord + Clone
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let tree = self.tree;
+        let t = self.stack.pop()?;
+        let v = &tree.value[t];
+        let in_range = match &self.end {
+            std::ops::Bound::Included(e) => v <= e,
+            std::ops::Bound::Excluded(e) => v < e,
+            std::ops::Bound::Unbounded => true,
+        };
+        if !in_range {
+            self.stack.clear();
+            return None;
         }
+        let mut c = tree.right[t];
+        while c != BTree::<T>::NIL {
+            self.stack.push(c);
+            c = tree.left[c];
+        }
+        Some(v)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
 
     #[test]
     fn test_binary_tree_u32() {
@@ -131,9 +304,9 @@ mod tests {
         tree.insert(4);
         tree.insert(9);
         assert!(!tree.is_empty());
-        assert!(tree.find(8));
-        assert!(tree.find(4));
-        assert!(!tree.find(2));
+        assert!(tree.find(&8));
+        assert!(tree.find(&4));
+        assert!(!tree.find(&2));
     }
 
     #[test]
@@ -161,4 +334,68 @@ mod tests {
         tree.insert(9);
         assert_eq!(tree.last(), Some(9));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_binary_tree_order_statistics() {
+        let mut tree = BTree::new();
+        for x in [5, 1, 9, 3, 7, 3] {
+            tree.insert(x);
+        }
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.kth(2), Some(5));
+        assert_eq!(tree.rank(&7), 3);
+        assert_eq!(tree.lower_bound(&4), Some(5));
+        assert_eq!(tree.upper_bound(&5), Some(7));
+        assert_eq!(tree.upper_bound(&9), None);
+        assert_eq!(tree.range(2..=7).copied().collect::<Vec<_>>(), vec![3, 5, 7]);
+        assert!(tree.remove(&5));
+        assert!(!tree.remove(&5));
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![1, 3, 7, 9]);
+    }
+
+    #[test]
+    fn test_binary_tree_random() {
+        let mut rng = rand::thread_rng();
+        for &multi in &[false, true] {
+            let mut tree = if multi { BTree::new_multi() } else { BTree::new() };
+            let mut naive: Vec<i32> = vec![];
+            for _ in 0..3000 {
+                let x = rng.sample(Uniform::from(0..100));
+                match rng.sample(Uniform::from(0..4)) {
+                    0 => {
+                        let inserted = multi || !naive.contains(&x);
+                        assert_eq!(tree.insert(x), inserted);
+                        if inserted {
+                            let p = naive.partition_point(|&y| y < x);
+                            naive.insert(p, x);
+                        }
+                    }
+                    1 => {
+                        let pos = naive.iter().position(|&y| y == x);
+                        assert_eq!(tree.remove(&x), pos.is_some());
+                        if let Some(p) = pos { naive.remove(p); }
+                    }
+                    2 => {
+                        let y = rng.sample(Uniform::from(0..100));
+                        let (lo, hi) = (x.min(y), x.max(y));
+                        let expected: Vec<i32> = naive.iter().copied().filter(|&v| lo <= v && v < hi).collect();
+                        assert_eq!(tree.range(lo..hi).copied().collect::<Vec<_>>(), expected);
+                    }
+                    _ => {
+                        assert_eq!(tree.find(&x), naive.contains(&x));
+                        assert_eq!(tree.count(&x), naive.iter().filter(|&&y| y == x).count());
+                        assert_eq!(tree.rank(&x), naive.partition_point(|&y| y < x));
+                        assert_eq!(tree.lower_bound(&x), naive.iter().copied().find(|&y| y >= x));
+                        assert_eq!(tree.upper_bound(&x), naive.iter().copied().find(|&y| y > x));
+                        let k = rng.sample(Uniform::from(0..=naive.len()));
+                        assert_eq!(tree.kth(k), naive.get(k).copied());
+                    }
+                }
+                assert_eq!(tree.len(), naive.len());
+                assert_eq!(tree.first(), naive.first().copied());
+                assert_eq!(tree.last(), naive.last().copied());
+            }
+            assert_eq!(tree.iter().copied().collect::<Vec<_>>(), naive);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("convex_hull_trick")]
+/// Convex hull trick for lines `y = a x + b` added in monotone order of slopes.
+///
+/// For `new_min()` slopes must be non-increasing, for `new_max()` non-decreasing.
+/// Use `i128` when `a * x` or the products of coefficients may overflow `i64`.
+pub struct MonotoneCHT<T> {
+    // lines of the min hull (max is stored negated), slopes decreasing
+    lines: std::collections::VecDeque<(T, T)>,
+    minimize: bool,
+}
+
+#[snippet("convex_hull_trick")]
+impl<T> MonotoneCHT<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed,
+{
+    pub fn new_min() -> Self {
+        Self { lines: std::collections::VecDeque::new(), minimize: true }
+    }
+
+    pub fn new_max() -> Self {
+        Self { lines: std::collections::VecDeque::new(), minimize: false }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    fn eval(l: &(T, T), x: T) -> T {
+        l.0 * x + l.1
+    }
+
+    // `l2` is never below both `l1` and `l3` (slopes a1 > a2 > a3)
+    fn unnecessary(l1: &(T, T), l2: &(T, T), l3: &(T, T)) -> bool {
+        (l2.1 - l1.1) * (l2.0 - l3.0) >= (l3.1 - l2.1) * (l1.0 - l2.0)
+    }
+
+    pub fn add_line(&mut self, a: T, b: T) {
+        let l = if self.minimize { (a, b) } else { (-a, -b) };
+        if let Some(&last) = self.lines.back() {
+            assert!(l.0 <= last.0, "slopes must be added in monotone order");
+            if l.0 == last.0 {
+                if l.1 >= last.1 { return }
+                self.lines.pop_back();
+            }
+        }
+        while self.lines.len() >= 2 {
+            let n = self.lines.len();
+            if Self::unnecessary(&self.lines[n - 2], &self.lines[n - 1], &l) {
+                self.lines.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.lines.push_back(l);
+    }
+
+    fn ret(&self, y: T) -> T {
+        if self.minimize { y } else { -y }
+    }
+
+    /// Min (or max) of the lines at `x`, in `O(log n)`. Panics if there is no line.
+    pub fn query(&self, x: T) -> T {
+        assert!(!self.lines.is_empty());
+        let (mut lo, mut hi) = (0, self.lines.len() - 1);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if Self::eval(&self.lines[mid], x) > Self::eval(&self.lines[mid + 1], x) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        self.ret(Self::eval(&self.lines[lo], x))
+    }
+
+    /// Same as `query` in amortized `O(1)`, for non-decreasing `x` across calls
+    /// (lines that can no longer be optimal are dropped).
+    pub fn query_monotone(&mut self, x: T) -> T {
+        assert!(!self.lines.is_empty());
+        while self.lines.len() >= 2 && Self::eval(&self.lines[0], x) >= Self::eval(&self.lines[1], x) {
+            self.lines.pop_front();
+        }
+        self.ret(Self::eval(&self.lines[0], x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_monotone_cht() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let mut lines: Vec<(i64, i64)> = (0..30)
+                .map(|_| (rng.sample(Uniform::from(-100..100)), rng.sample(Uniform::from(-1000..1000))))
+                .collect();
+            lines.sort_by_key(|p| std::cmp::Reverse(p.0));
+            let mut min_cht = MonotoneCHT::new_min();
+            let mut max_cht = MonotoneCHT::new_max();
+            for &(a, b) in &lines {
+                min_cht.add_line(a, b);
+            }
+            for &(a, b) in lines.iter().rev() {
+                max_cht.add_line(a, b);
+            }
+            for x in -50..50 {
+                let ys = lines.iter().map(|&(a, b)| a * x + b);
+                assert_eq!(min_cht.query(x), ys.clone().min().unwrap());
+                assert_eq!(max_cht.query(x), ys.clone().max().unwrap());
+                assert_eq!(min_cht.query_monotone(x), ys.min().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_monotone_cht_i128() {
+        let mut cht = MonotoneCHT::<i128>::new_min();
+        let big = 1_000_000_000_000_000_000i128;
+        cht.add_line(big, 0);
+        cht.add_line(0, big);
+        cht.add_line(-big, 5 * big);
+        assert_eq!(cht.query(big), -big * big + 5 * big);
+        assert_eq!(cht.query(2), big);
+        assert_eq!(cht.query(-1), -big);
+        assert_eq!(cht.query(10), -5 * big);
+    }
+}
@@ -0,0 +1,469 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::{MapMonoid, Monoid};
+
+#[snippet("dynamic_segtree", include = "monoid")]
+/// Segment tree over `[0, n)` for huge `n` (e.g. `1e18`), allocating nodes on demand.
+///
+/// Absent nodes are the identity; each `update` allocates at most `O(log n)` nodes.
+pub struct DynamicSegTree<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    val: Vec<M::S>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    m: M,
+}
+
+#[snippet("dynamic_segtree", include = "monoid")]
+impl<M> DynamicSegTree<M>
+where
+    M: Monoid,
+{
+    const NIL: usize = usize::MAX;
+
+    pub fn new(n: usize, m: M) -> Self {
+        let mut seg = Self { n, val: vec![], left: vec![], right: vec![], m };
+        seg.new_node();
+        seg
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn new_node(&mut self) -> usize {
+        self.val.push(self.m.identity());
+        self.left.push(Self::NIL);
+        self.right.push(Self::NIL);
+        self.val.len() - 1
+    }
+
+    fn value(&self, k: usize) -> M::S {
+        if k == Self::NIL { self.m.identity() } else { self.val[k].clone() }
+    }
+
+    pub fn update(&mut self, k: usize, v: M::S) {
+        assert!(k < self.n);
+        let (mut node, mut l, mut r) = (0, 0, self.n);
+        let mut path = vec![];
+        while r - l > 1 {
+            path.push(node);
+            let mid = l + (r - l) / 2;
+            if k < mid {
+                if self.left[node] == Self::NIL {
+                    let c = self.new_node();
+                    self.left[node] = c;
+                }
+                node = self.left[node];
+                r = mid;
+            } else {
+                if self.right[node] == Self::NIL {
+                    let c = self.new_node();
+                    self.right[node] = c;
+                }
+                node = self.right[node];
+                l = mid;
+            }
+        }
+        self.val[node] = v;
+        for &p in path.iter().rev() {
+            self.val[p] = self.m.op(&self.value(self.left[p]), &self.value(self.right[p]));
+        }
+    }
+
+    pub fn get(&self, k: usize) -> M::S {
+        assert!(k < self.n);
+        let (mut node, mut l, mut r) = (0, 0, self.n);
+        while r - l > 1 && node != Self::NIL {
+            let mid = l + (r - l) / 2;
+            if k < mid {
+                node = self.left[node];
+                r = mid;
+            } else {
+                node = self.right[node];
+                l = mid;
+            }
+        }
+        self.value(node)
+    }
+
+    fn query_rec(&self, k: usize, l: usize, r: usize, a: usize, b: usize) -> M::S {
+        if k == Self::NIL || b <= l || r <= a { return self.m.identity() }
+        if a <= l && r <= b { return self.val[k].clone() }
+        let mid = l + (r - l) / 2;
+        self.m.op(
+            &self.query_rec(self.left[k], l, mid, a, b),
+            &self.query_rec(self.right[k], mid, r, a, b),
+        )
+    }
+
+    /// `a[i] * a[i+1] * ... * a[j-1]`
+    pub fn query(&self, i: usize, j: usize) -> M::S {
+        assert!(i <= j && j <= self.n);
+        if i == j { return self.m.identity() }
+        self.query_rec(0, 0, self.n, i, j)
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.val[0].clone()
+    }
+
+    // First position in [max(l, lo), hi) where the fold from `l` stops satisfying `pred`.
+    fn max_right_rec<P>(&self, k: usize, lo: usize, hi: usize, l: usize, pred: &P, sm: &mut M::S) -> Option<usize>
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        if hi <= l || k == Self::NIL { return None }
+        if l <= lo {
+            let res = self.m.op(sm, &self.val[k]);
+            if pred(&res) {
+                *sm = res;
+                return None;
+            }
+            if hi - lo == 1 { return Some(lo) }
+        }
+        let mid = lo + (hi - lo) / 2;
+        self.max_right_rec(self.left[k], lo, mid, l, pred, sm)
+            .or_else(|| self.max_right_rec(self.right[k], mid, hi, l, pred, sm))
+    }
+
+    // Symmetric to `max_right_rec`; returns the position just after the failing element.
+    fn min_left_rec<P>(&self, k: usize, lo: usize, hi: usize, r: usize, pred: &P, sm: &mut M::S) -> Option<usize>
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        if r <= lo || k == Self::NIL { return None }
+        if hi <= r {
+            let res = self.m.op(&self.val[k], sm);
+            if pred(&res) {
+                *sm = res;
+                return None;
+            }
+            if hi - lo == 1 { return Some(hi) }
+        }
+        let mid = lo + (hi - lo) / 2;
+        self.min_left_rec(self.right[k], mid, hi, r, pred, sm)
+            .or_else(|| self.min_left_rec(self.left[k], lo, mid, r, pred, sm))
+    }
+
+    /// Maximum `r` such that `pred(query(l, r))` holds, assuming `pred` is monotone
+    /// and `pred(identity)` is true.
+    pub fn max_right<P>(&self, l: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(l <= self.n);
+        let mut sm = self.m.identity();
+        assert!(pred(&sm));
+        if l == self.n { return self.n }
+        self.max_right_rec(0, 0, self.n, l, &pred, &mut sm).unwrap_or(self.n)
+    }
+
+    /// Minimum `l` such that `pred(query(l, r))` holds, assuming `pred` is monotone
+    /// and `pred(identity)` is true.
+    pub fn min_left<P>(&self, r: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(r <= self.n);
+        let mut sm = self.m.identity();
+        assert!(pred(&sm));
+        if r == 0 { return 0 }
+        self.min_left_rec(0, 0, self.n, r, &pred, &mut sm).unwrap_or(0)
+    }
+}
+
+#[snippet("dynamic_lazy_segtree", include = "map_monoid")]
+/// `LazySegTree` over `[0, n)` for huge `n`, allocating nodes on demand.
+///
+/// Every position starts as the same value `x`; an untouched node over a segment of length
+/// `len` holds `x * x * ... * x` (`len` times), computed by doubling and cached per length.
+pub struct DynamicLazySegTree<M>
+where
+    M: MapMonoid,
+{
+    n: usize,
+    x: M::S,
+    pow_cache: std::collections::HashMap<usize, M::S>,
+    val: Vec<M::S>,
+    lazy: Vec<M::F>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    m: M,
+}
+
+#[snippet("dynamic_lazy_segtree", include = "map_monoid")]
+impl<M> DynamicLazySegTree<M>
+where
+    M: MapMonoid,
+{
+    const NIL: usize = usize::MAX;
+
+    pub fn new(n: usize, m: M) -> Self {
+        Self::from_value(n, m.identity(), m)
+    }
+
+    /// All `n` positions start as `x`.
+    pub fn from_value(n: usize, x: M::S, m: M) -> Self {
+        let mut seg = Self {
+            n,
+            x,
+            pow_cache: std::collections::HashMap::new(),
+            val: vec![],
+            lazy: vec![],
+            left: vec![],
+            right: vec![],
+            m,
+        };
+        seg.new_node(n);
+        seg
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn pow(&mut self, len: usize) -> M::S {
+        if let Some(v) = self.pow_cache.get(&len) {
+            return v.clone();
+        }
+        let (mut res, mut b, mut e) = (self.m.identity(), self.x.clone(), len);
+        while e > 0 {
+            if e & 1 == 1 { res = self.m.op(&res, &b); }
+            b = self.m.op(&b, &b);
+            e >>= 1;
+        }
+        self.pow_cache.insert(len, res.clone());
+        res
+    }
+
+    fn new_node(&mut self, len: usize) -> usize {
+        let v = self.pow(len);
+        self.val.push(v);
+        self.lazy.push(self.m.identity_map());
+        self.left.push(Self::NIL);
+        self.right.push(Self::NIL);
+        self.val.len() - 1
+    }
+
+    fn recalc(&mut self, k: usize) {
+        self.val[k] = self.m.op(&self.val[self.left[k]], &self.val[self.right[k]]);
+    }
+
+    fn reflect(&mut self, k: usize, f: &M::F) {
+        self.val[k] = self.m.mapping(f, &self.val[k]);
+        self.lazy[k] = self.m.composition(f, &self.lazy[k]);
+    }
+
+    // Creates the children of `k` if needed and pushes `lazy[k]` down to them.
+    fn propagate(&mut self, k: usize, l: usize, r: usize) {
+        let mid = l + (r - l) / 2;
+        if self.left[k] == Self::NIL {
+            let c = self.new_node(mid - l);
+            self.left[k] = c;
+        }
+        if self.right[k] == Self::NIL {
+            let c = self.new_node(r - mid);
+            self.right[k] = c;
+        }
+        let f = std::mem::replace(&mut self.lazy[k], self.m.identity_map());
+        self.reflect(self.left[k], &f);
+        self.reflect(self.right[k], &f);
+    }
+
+    fn set_rec(&mut self, k: usize, l: usize, r: usize, p: usize, x: M::S) {
+        if r - l == 1 {
+            self.val[k] = x;
+            return;
+        }
+        self.propagate(k, l, r);
+        let mid = l + (r - l) / 2;
+        if p < mid {
+            self.set_rec(self.left[k], l, mid, p, x);
+        } else {
+            self.set_rec(self.right[k], mid, r, p, x);
+        }
+        self.recalc(k);
+    }
+
+    fn update_rec(&mut self, k: usize, l: usize, r: usize, a: usize, b: usize, f: &M::F) {
+        if b <= l || r <= a { return }
+        if a <= l && r <= b {
+            self.reflect(k, f);
+            return;
+        }
+        self.propagate(k, l, r);
+        let mid = l + (r - l) / 2;
+        self.update_rec(self.left[k], l, mid, a, b, f);
+        self.update_rec(self.right[k], mid, r, a, b, f);
+        self.recalc(k);
+    }
+
+    fn query_rec(&mut self, k: usize, l: usize, r: usize, a: usize, b: usize) -> M::S {
+        if b <= l || r <= a { return self.m.identity() }
+        if a <= l && r <= b { return self.val[k].clone() }
+        self.propagate(k, l, r);
+        let mid = l + (r - l) / 2;
+        let vl = self.query_rec(self.left[k], l, mid, a, b);
+        let vr = self.query_rec(self.right[k], mid, r, a, b);
+        self.m.op(&vl, &vr)
+    }
+
+    pub fn set(&mut self, p: usize, x: M::S) {
+        assert!(p < self.n);
+        self.set_rec(0, 0, self.n, p, x);
+    }
+
+    pub fn get(&mut self, p: usize) -> M::S {
+        assert!(p < self.n);
+        self.query_rec(0, 0, self.n, p, p + 1)
+    }
+
+    /// `a[p] = f(a[p])`
+    pub fn apply(&mut self, p: usize, f: M::F) {
+        assert!(p < self.n);
+        self.update_rec(0, 0, self.n, p, p + 1, &f);
+    }
+
+    /// `a[k] = f(a[k])` for `i <= k < j`
+    pub fn update(&mut self, i: usize, j: usize, f: M::F) {
+        assert!(i <= j && j <= self.n);
+        if i == j { return }
+        self.update_rec(0, 0, self.n, i, j, &f);
+    }
+
+    /// `a[i] * a[i+1] * ... * a[j-1]`
+    pub fn query(&mut self, i: usize, j: usize) -> M::S {
+        assert!(i <= j && j <= self.n);
+        if i == j { return self.m.identity() }
+        self.query_rec(0, 0, self.n, i, j)
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.val[0].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_dynamic_segtree_huge() {
+        let mut rng = rand::thread_rng();
+        let n = 1_000_000_000_000_000_000;
+        let mut seg = DynamicSegTree::new(n, Sum::new());
+        let mut naive = BTreeMap::new();
+        for _ in 0..500 {
+            let k = rng.sample(Uniform::from(0..n));
+            let x = rng.sample(Uniform::from(-1000..1000i64));
+            seg.update(k, x);
+            naive.insert(k, x);
+            assert_eq!(seg.get(k), x);
+
+            let l = rng.sample(Uniform::from(0..=n));
+            let r = rng.sample(Uniform::from(l..=n));
+            assert_eq!(seg.query(l, r), naive.range(l..r).map(|(_, v)| v).sum::<i64>());
+        }
+        assert_eq!(seg.all_prod(), naive.values().sum::<i64>());
+        assert_eq!(seg.get(n - 1), *naive.get(&(n - 1)).unwrap_or(&0));
+    }
+
+    #[test]
+    fn test_dynamic_segtree_max_right_min_left() {
+        let mut rng = rand::thread_rng();
+        for n in 1..30 {
+            let mut a = vec![0i64; n];
+            let mut seg = DynamicSegTree::new(n, Sum::new());
+            for _ in 0..30 {
+                let k = rng.sample(Uniform::from(0..n));
+                a[k] = rng.sample(Uniform::from(0..10));
+                seg.update(k, a[k]);
+                let x = rng.sample(Uniform::from(0..40));
+                for l in 0..=n {
+                    let (mut r, mut s) = (l, 0);
+                    while r < n && s + a[r] <= x {
+                        s += a[r];
+                        r += 1;
+                    }
+                    assert_eq!(seg.max_right(l, |&s| s <= x), r);
+                }
+                for r in 0..=n {
+                    let (mut l, mut s) = (r, 0);
+                    while l > 0 && s + a[l - 1] <= x {
+                        s += a[l - 1];
+                        l -= 1;
+                    }
+                    assert_eq!(seg.min_left(r, |&s| s <= x), l);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_lazy_segtree_small() {
+        let mut rng = rand::thread_rng();
+        for n in 1..30 {
+            let mut a = vec![3i64; n];
+            let mut seg = DynamicLazySegTree::from_value(n, (3i64, 1i64), RangeAddRangeSum::new());
+            for _ in 0..100 {
+                let l = rng.sample(Uniform::from(0..n));
+                let r = rng.sample(Uniform::from(l..=n));
+                match rng.sample(Uniform::from(0..3)) {
+                    0 => {
+                        let x = rng.sample(Uniform::from(-10..10));
+                        seg.update(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v += x);
+                    }
+                    1 => {
+                        let x = rng.sample(Uniform::from(-10..10));
+                        seg.set(l, (x, 1));
+                        a[l] = x;
+                        assert_eq!(seg.get(l), (x, 1));
+                    }
+                    _ => {
+                        let s = a[l..r].iter().sum::<i64>();
+                        assert_eq!(seg.query(l, r), (s, (r - l) as i64));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_lazy_segtree_huge() {
+        let n: usize = 1_000_000_000_000_000_000;
+        let mut seg = DynamicLazySegTree::from_value(n, (0i128, 1i128), RangeAddRangeSum::new());
+        seg.update(0, n, 1);
+        seg.update(n / 2, n, 2);
+        seg.apply(7, -1);
+        let n128 = n as i128;
+        assert_eq!(seg.all_prod(), (n128 + n128 / 2 * 2 - 1, n128));
+        assert_eq!(seg.query(5, 10), (4, 5));
+        assert_eq!(seg.query(n / 2 - 1, n / 2 + 2), (1 + 3 + 3, 3));
+        assert_eq!(seg.get(n - 1), (3, 1));
+
+        let mut seg = DynamicLazySegTree::new(n, RangeChminRangeMax::new());
+        seg.set(123, 10);
+        seg.set(n - 5, 20);
+        assert_eq!(seg.query(0, n), 20);
+        seg.update(100, n, 15);
+        assert_eq!(seg.query(0, n), 15);
+        assert_eq!(seg.query(0, 1000), 10);
+    }
+}
@@ -0,0 +1,183 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::bit::BIT;
+use crate::data_structure::monoid::Sum;
+
+#[snippet("fenwick_multiset", include = "bit")]
+/// Ordered multiset over a universe of values known in advance, backed by a `BIT` of counts.
+/// Every operation is `O(log n)` where `n` is the size of the universe.
+pub struct FenwickMultiset<T> {
+    xs: Vec<T>,
+    bit: BIT<Sum<i64>>,
+    len: usize,
+}
+
+#[snippet("fenwick_multiset", include = "bit")]
+impl<T> FenwickMultiset<T>
+where
+    T: Clone + Ord,
+{
+    /// `universe` lists every value that may be inserted (duplicates are fine).
+    pub fn new(universe: &[T]) -> Self {
+        let mut xs = universe.to_vec();
+        xs.sort();
+        xs.dedup();
+        let bit = BIT::new(xs.len(), Sum::new());
+        Self { xs, bit, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, x: T) {
+        let i = self.xs.binary_search(&x).expect("value not in the universe");
+        self.bit.add(i, 1);
+        self.len += 1;
+    }
+
+    /// Removes one occurrence of `x`; returns `false` if there was none.
+    pub fn remove(&mut self, x: &T) -> bool {
+        match self.xs.binary_search(x) {
+            Ok(i) if self.bit.sum(i, i + 1) > 0 => {
+                self.bit.add(i, -1);
+                self.len -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn count(&self, x: &T) -> usize {
+        match self.xs.binary_search(x) {
+            Ok(i) => self.bit.sum(i, i + 1) as usize,
+            Err(_) => 0,
+        }
+    }
+
+    /// The `k`-th (0-indexed) smallest element
+    pub fn kth(&self, k: usize) -> Option<T> {
+        if k >= self.len { return None }
+        let i = self.bit.lower_bound(k as i64 + 1);
+        Some(self.xs[i].clone())
+    }
+
+    /// Number of elements `< x`
+    pub fn rank(&self, x: &T) -> usize {
+        let i = self.xs.partition_point(|y| y < x);
+        self.bit.prefix_sum(i) as usize
+    }
+
+    /// Largest element `<= x`
+    pub fn prev(&self, x: &T) -> Option<T> {
+        let i = self.xs.partition_point(|y| y <= x);
+        let c = self.bit.prefix_sum(i) as usize;
+        if c == 0 { None } else { self.kth(c - 1) }
+    }
+
+    /// Smallest element `>= x`
+    pub fn next(&self, x: &T) -> Option<T> {
+        self.kth(self.rank(x))
+    }
+}
+
+#[snippet("fenwick_multiset", include = "bit")]
+/// Number of pairs `i < j` with `a[i] > a[j]`, in `O(n log n)`.
+pub fn inversion_count<T: Clone + Ord>(a: &[T]) -> u64 {
+    let mut xs = a.to_vec();
+    xs.sort();
+    xs.dedup();
+    let mut bit = BIT::new(xs.len(), Sum::new());
+    let mut inv = 0;
+    for (seen, x) in a.iter().enumerate() {
+        let i = xs.binary_search(x).unwrap();
+        inv += seen as u64 - bit.prefix_sum(i + 1);
+        bit.add(i, 1u64);
+    }
+    inv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_fenwick_multiset_small() {
+        let mut ms = FenwickMultiset::new(&[10, 20, 30, 40]);
+        ms.insert(30);
+        ms.insert(10);
+        ms.insert(30);
+        assert_eq!(ms.len(), 3);
+        assert_eq!(ms.count(&30), 2);
+        assert_eq!(ms.count(&25), 0);
+        assert_eq!(ms.kth(0), Some(10));
+        assert_eq!(ms.kth(2), Some(30));
+        assert_eq!(ms.kth(3), None);
+        assert_eq!(ms.rank(&30), 1);
+        assert_eq!(ms.rank(&31), 3);
+        assert_eq!(ms.prev(&29), Some(10));
+        assert_eq!(ms.prev(&9), None);
+        assert_eq!(ms.next(&11), Some(30));
+        assert_eq!(ms.next(&31), None);
+        assert!(ms.remove(&30));
+        assert!(!ms.remove(&20));
+        assert!(!ms.remove(&15));
+        assert_eq!(ms.count(&30), 1);
+    }
+
+    #[test]
+    fn test_fenwick_multiset_random() {
+        let mut rng = rand::thread_rng();
+        let universe: Vec<i32> = (0..30).map(|x| x * 3).collect();
+        let mut ms = FenwickMultiset::new(&universe);
+        let mut naive: Vec<i32> = vec![];
+        for _ in 0..2000 {
+            let x = universe[rng.sample(Uniform::from(0..universe.len()))];
+            let q = rng.sample(Uniform::from(-5..95));
+            match rng.sample(Uniform::from(0..3)) {
+                0 => {
+                    ms.insert(x);
+                    naive.push(x);
+                    naive.sort();
+                }
+                1 => {
+                    let pos = naive.iter().position(|&y| y == x);
+                    assert_eq!(ms.remove(&x), pos.is_some());
+                    if let Some(p) = pos { naive.remove(p); }
+                }
+                _ => {
+                    assert_eq!(ms.count(&q), naive.iter().filter(|&&y| y == q).count());
+                    assert_eq!(ms.rank(&q), naive.iter().filter(|&&y| y < q).count());
+                    assert_eq!(ms.prev(&q), naive.iter().rev().find(|&&y| y <= q).copied());
+                    assert_eq!(ms.next(&q), naive.iter().find(|&&y| y >= q).copied());
+                    let k = rng.sample(Uniform::from(0..=naive.len()));
+                    assert_eq!(ms.kth(k), naive.get(k).copied());
+                }
+            }
+            assert_eq!(ms.len(), naive.len());
+        }
+    }
+
+    #[test]
+    fn test_inversion_count() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(inversion_count::<i32>(&[]), 0);
+        assert_eq!(inversion_count(&[3, 1, 2]), 2);
+        for _ in 0..20 {
+            let a: Vec<i32> = (0..50).map(|_| rng.sample(Uniform::from(0..10))).collect();
+            let mut naive = 0;
+            for i in 0..a.len() {
+                for j in i + 1..a.len() {
+                    if a[i] > a[j] { naive += 1; }
+                }
+            }
+            assert_eq!(inversion_count(&a), naive);
+        }
+    }
+}
@@ -0,0 +1,370 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::MapMonoid;
+
+#[snippet("implicit_treap", include = "map_monoid")]
+/// Sequence on an implicit-key treap over a `MapMonoid`: insert / erase at any position,
+/// range reverse, range fold and range application of maps, all in expected `O(log n)`.
+///
+/// Each node keeps the fold of its subtree in both directions, so reversing a range
+/// is just swapping them (the monoid does not have to be commutative).
+///
+/// The struct is an arena that may hold several sequences: the sequence methods act on
+/// `root()`, while `split` / `merge` work on any node handle (`NIL` is the empty sequence).
+/// ```rust
+/// # use snippets::data_structure::monoid::*;
+/// # use snippets::data_structure::implicit_treap::*;
+/// let mut t = ImplicitTreap::from_vec(vec![(1i64, 1i64), (2, 1), (3, 1), (4, 1)], RangeAddRangeSum::new());
+/// // move [1, 3) to the back
+/// let (a, b) = t.split(t.root(), 1);
+/// let (b, c) = t.split(b, 2);
+/// let ac = t.merge(a, c);
+/// let root = t.merge(ac, b);
+/// t.set_root(root);
+/// assert_eq!(t.to_vec(), vec![(1, 1), (4, 1), (2, 1), (3, 1)]);
+/// ```
+pub struct ImplicitTreap<M>
+where
+    M: MapMonoid,
+{
+    root: usize,
+    val: Vec<M::S>,
+    prod: Vec<M::S>,
+    rprod: Vec<M::S>,
+    lazy: Vec<M::F>,
+    rev: Vec<bool>,
+    priority: Vec<u64>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    size: Vec<usize>,
+    free: Vec<usize>,
+    seed: u64,
+    m: M,
+}
+
+#[snippet("implicit_treap", include = "map_monoid")]
+impl<M> ImplicitTreap<M>
+where
+    M: MapMonoid,
+{
+    pub const NIL: usize = usize::MAX;
+
+    pub fn new(m: M) -> Self {
+        Self {
+            root: Self::NIL,
+            val: vec![],
+            prod: vec![],
+            rprod: vec![],
+            lazy: vec![],
+            rev: vec![],
+            priority: vec![],
+            left: vec![],
+            right: vec![],
+            size: vec![],
+            free: vec![],
+            seed: 88172645463325252,
+            m,
+        }
+    }
+
+    pub fn from_vec(v: Vec<M::S>, m: M) -> Self {
+        let mut t = Self::new(m);
+        for x in v {
+            let k = t.new_node(x);
+            t.root = t.merge(t.root, k);
+        }
+        t
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == Self::NIL
+    }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    pub fn set_root(&mut self, t: usize) {
+        self.root = t;
+    }
+
+    fn new_node(&mut self, x: M::S) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let p = self.seed;
+        let id = self.m.identity_map();
+        if let Some(t) = self.free.pop() {
+            self.val[t] = x.clone();
+            self.prod[t] = x.clone();
+            self.rprod[t] = x;
+            self.lazy[t] = id;
+            self.rev[t] = false;
+            self.priority[t] = p;
+            self.left[t] = Self::NIL;
+            self.right[t] = Self::NIL;
+            self.size[t] = 1;
+            t
+        } else {
+            self.val.push(x.clone());
+            self.prod.push(x.clone());
+            self.rprod.push(x);
+            self.lazy.push(id);
+            self.rev.push(false);
+            self.priority.push(p);
+            self.left.push(Self::NIL);
+            self.right.push(Self::NIL);
+            self.size.push(1);
+            self.val.len() - 1
+        }
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == Self::NIL { 0 } else { self.size[t] }
+    }
+
+    fn prod_of(&self, t: usize) -> M::S {
+        if t == Self::NIL { self.m.identity() } else { self.prod[t].clone() }
+    }
+
+    fn rprod_of(&self, t: usize) -> M::S {
+        if t == Self::NIL { self.m.identity() } else { self.rprod[t].clone() }
+    }
+
+    fn recalc(&mut self, t: usize) {
+        let (l, r) = (self.left[t], self.right[t]);
+        self.size[t] = 1 + self.size(l) + self.size(r);
+        self.prod[t] = self.m.op(&self.m.op(&self.prod_of(l), &self.val[t]), &self.prod_of(r));
+        self.rprod[t] = self.m.op(&self.m.op(&self.rprod_of(r), &self.val[t]), &self.rprod_of(l));
+    }
+
+    fn reflect(&mut self, t: usize, f: &M::F) {
+        if t == Self::NIL { return }
+        self.val[t] = self.m.mapping(f, &self.val[t]);
+        self.prod[t] = self.m.mapping(f, &self.prod[t]);
+        self.rprod[t] = self.m.mapping(f, &self.rprod[t]);
+        self.lazy[t] = self.m.composition(f, &self.lazy[t]);
+    }
+
+    fn toggle(&mut self, t: usize) {
+        if t == Self::NIL { return }
+        std::mem::swap(&mut self.left[t], &mut self.right[t]);
+        std::mem::swap(&mut self.prod[t], &mut self.rprod[t]);
+        self.rev[t] ^= true;
+    }
+
+    fn propagate(&mut self, t: usize) {
+        let f = std::mem::replace(&mut self.lazy[t], self.m.identity_map());
+        self.reflect(self.left[t], &f);
+        self.reflect(self.right[t], &f);
+        if self.rev[t] {
+            self.toggle(self.left[t]);
+            self.toggle(self.right[t]);
+            self.rev[t] = false;
+        }
+    }
+
+    /// Splits the sequence `t` into its first `k` elements and the rest.
+    pub fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        if t == Self::NIL { return (Self::NIL, Self::NIL) }
+        self.propagate(t);
+        let ls = self.size(self.left[t]);
+        if k <= ls {
+            let (a, b) = self.split(self.left[t], k);
+            self.left[t] = b;
+            self.recalc(t);
+            (a, t)
+        } else {
+            let (a, b) = self.split(self.right[t], k - ls - 1);
+            self.right[t] = a;
+            self.recalc(t);
+            (t, b)
+        }
+    }
+
+    /// Concatenates the sequences `a` and `b`.
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == Self::NIL { return b }
+        if b == Self::NIL { return a }
+        if self.priority[a] > self.priority[b] {
+            self.propagate(a);
+            self.right[a] = self.merge(self.right[a], b);
+            self.recalc(a);
+            a
+        } else {
+            self.propagate(b);
+            self.left[b] = self.merge(a, self.left[b]);
+            self.recalc(b);
+            b
+        }
+    }
+
+    // Cuts the main sequence into [0, l), [l, r), [r, len).
+    fn cut(&mut self, l: usize, r: usize) -> (usize, usize, usize) {
+        assert!(l <= r && r <= self.len());
+        let (a, b) = self.split(self.root, l);
+        let (b, c) = self.split(b, r - l);
+        (a, b, c)
+    }
+
+    fn join(&mut self, a: usize, b: usize, c: usize) {
+        let ab = self.merge(a, b);
+        self.root = self.merge(ab, c);
+    }
+
+    /// Inserts `x` so that it becomes the `i`-th element.
+    pub fn insert(&mut self, i: usize, x: M::S) {
+        assert!(i <= self.len());
+        let (a, c) = self.split(self.root, i);
+        let b = self.new_node(x);
+        self.join(a, b, c);
+    }
+
+    /// Removes and returns the `i`-th element.
+    pub fn erase(&mut self, i: usize) -> M::S {
+        assert!(i < self.len());
+        let (a, b, c) = self.cut(i, i + 1);
+        self.free.push(b);
+        let x = self.val[b].clone();
+        self.root = self.merge(a, c);
+        x
+    }
+
+    pub fn get(&mut self, i: usize) -> M::S {
+        self.query(i, i + 1)
+    }
+
+    pub fn set(&mut self, i: usize, x: M::S) {
+        let (a, b, c) = self.cut(i, i + 1);
+        self.val[b] = x;
+        self.recalc(b);
+        self.join(a, b, c);
+    }
+
+    /// Reverses `a[l..r]`.
+    pub fn reverse(&mut self, l: usize, r: usize) {
+        let (a, b, c) = self.cut(l, r);
+        self.toggle(b);
+        self.join(a, b, c);
+    }
+
+    /// `a[k] = f(a[k])` for `l <= k < r`
+    pub fn update(&mut self, l: usize, r: usize, f: M::F) {
+        let (a, b, c) = self.cut(l, r);
+        self.reflect(b, &f);
+        self.join(a, b, c);
+    }
+
+    /// `a[l] * a[l+1] * ... * a[r-1]`
+    pub fn query(&mut self, l: usize, r: usize) -> M::S {
+        let (a, b, c) = self.cut(l, r);
+        let x = self.prod_of(b);
+        self.join(a, b, c);
+        x
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.prod_of(self.root)
+    }
+
+    fn collect(&mut self, t: usize, v: &mut Vec<M::S>) {
+        if t == Self::NIL { return }
+        self.propagate(t);
+        self.collect(self.left[t], v);
+        v.push(self.val[t].clone());
+        self.collect(self.right[t], v);
+    }
+
+    pub fn to_vec(&mut self) -> Vec<M::S> {
+        let mut v = Vec::with_capacity(self.len());
+        self.collect(self.root, &mut v);
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_implicit_treap_range_add_sum() {
+        let mut rng = rand::thread_rng();
+        let mut a: Vec<i64> = vec![];
+        let mut t = ImplicitTreap::new(RangeAddRangeSum::new());
+        for _ in 0..2000 {
+            let n = a.len();
+            match rng.sample(Uniform::from(0..6)) {
+                0 | 1 => {
+                    let i = rng.sample(Uniform::from(0..=n));
+                    let x = rng.sample(Uniform::from(-100..100));
+                    t.insert(i, (x, 1));
+                    a.insert(i, x);
+                }
+                2 if n > 0 => {
+                    let i = rng.sample(Uniform::from(0..n));
+                    assert_eq!(t.erase(i), (a.remove(i), 1));
+                }
+                3 => {
+                    let l = rng.sample(Uniform::from(0..=n));
+                    let r = rng.sample(Uniform::from(l..=n));
+                    t.reverse(l, r);
+                    a[l..r].reverse();
+                }
+                4 => {
+                    let l = rng.sample(Uniform::from(0..=n));
+                    let r = rng.sample(Uniform::from(l..=n));
+                    let x = rng.sample(Uniform::from(-10..10));
+                    t.update(l, r, x);
+                    a[l..r].iter_mut().for_each(|v| *v += x);
+                }
+                _ => {
+                    let l = rng.sample(Uniform::from(0..=n));
+                    let r = rng.sample(Uniform::from(l..=n));
+                    let s = a[l..r].iter().sum::<i64>();
+                    assert_eq!(t.query(l, r), (s, (r - l) as i64));
+                }
+            }
+            assert_eq!(t.len(), a.len());
+        }
+        assert_eq!(t.to_vec(), a.iter().map(|&x| (x, 1)).collect::<Vec<_>>());
+        assert_eq!(t.all_prod().0, a.iter().sum::<i64>());
+    }
+
+    #[test]
+    fn test_implicit_treap_reverse_non_commutative() {
+        let mut rng = rand::thread_rng();
+        let n = 40;
+        let mut a: Vec<(Wrapping<u64>, Wrapping<u64>)> =
+            (0..n).map(|_| (Wrapping(rng.gen()), Wrapping(rng.gen()))).collect();
+        let m = Affine::new();
+        let mut t = ImplicitTreap::from_vec(a.clone(), NoMap(m));
+        for _ in 0..500 {
+            let l = rng.sample(Uniform::from(0..=n));
+            let r = rng.sample(Uniform::from(l..=n));
+            match rng.sample(Uniform::from(0..3)) {
+                0 => {
+                    t.reverse(l, r);
+                    a[l..r].reverse();
+                }
+                1 if l < n => {
+                    let x = (Wrapping(rng.gen()), Wrapping(rng.gen()));
+                    t.set(l, x);
+                    a[l] = x;
+                    assert_eq!(t.get(l), x);
+                }
+                _ => {
+                    let expected = a[l..r].iter().fold(m.identity(), |acc, f| m.op(&acc, f));
+                    assert_eq!(t.query(l, r), expected);
+                }
+            }
+        }
+    }
+}
@@ -1,166 +1,372 @@
 #![allow(dead_code)]
 use cargo_snippet::snippet;
+use crate::data_structure::monoid::MapMonoid;
 
-#[snippet("lazysegtree")]
-struct LazySegTree<Monoid, Operator>
+#[snippet("lazysegtree", include = "map_monoid")]
+/// Segment tree with range application of maps, over a `MapMonoid`.
+///
+/// `data[k]` already has `lazy[k]` applied; `lazy[k]` is pending for the children only.
+pub struct LazySegTree<M>
+where
+    M: MapMonoid,
 {
+    n: usize,
     size: usize,
     height: usize,
-    data: Vec<Monoid>,
-    lazy: Vec<Operator>,
-    f: fn(&Monoid, &Monoid) -> Monoid,
-    g: fn(&Monoid, &Operator) -> Monoid,
-    h: fn(&Operator, &Operator) -> Operator,
-    m0: Monoid,
-    o0: Operator,
+    data: Vec<M::S>,
+    lazy: Vec<M::F>,
+    m: M,
 }
 
-impl<Monoid, Operator> LazySegTree<Monoid, Operator> 
+#[snippet("lazysegtree", include = "map_monoid")]
+impl<M> LazySegTree<M>
 where
-    Monoid: Copy + Clone,
-    Operator: Eq + Copy + Clone,
+    M: MapMonoid,
 {
-    fn new(
-        n: usize,
-        f: fn(&Monoid, &Monoid) -> Monoid,
-        g: fn(&Monoid, &Operator) -> Monoid,
-        h: fn(&Operator, &Operator) -> Operator,
-        m0: Monoid,
-        o0: Operator,
-    ) -> Self {
+    pub fn new(n: usize, m: M) -> Self {
+        Self::from_vec(vec![m.identity(); n], m)
+    }
+
+    pub fn from_vec(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
         let mut size = 1;
         let mut height = 0;
         while size < n {
             size <<= 1;
             height += 1;
         }
-        let data = vec![m0; 2 * size];
-        let lazy = vec![o0; 2 * size];
-        Self { size, height, data, lazy, f, g, h, m0, o0 }
+        let mut data = vec![m.identity(); 2 * size];
+        for (k, x) in v.into_iter().enumerate() {
+            data[size + k] = x;
+        }
+        let lazy = vec![m.identity_map(); size];
+        let mut seg = Self { n, size, height, data, lazy, m };
+        for k in (1..size).rev() {
+            seg.recalc(k);
+        }
+        seg
     }
 
-    fn set(&mut self, k: usize, v: Monoid) {
-        self.data[self.size + k] = v;
+    pub fn len(&self) -> usize {
+        self.n
     }
 
-    fn build(&mut self) {
-        for k in (1..=self.size-1).rev() {
-            self.data[k] = (self.f)(&self.data[(k<<1)|0], &self.data[(k<<1)|1]);
-        }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
     }
 
-    fn propagate(&mut self, k: usize) {
-        if self.lazy[k] == self.o0 { return }
-        self.lazy[(k<<1)|0] = (self.h)(&self.lazy[(k<<1)|0], &self.lazy[k]);
-        self.lazy[(k<<1)|1] = (self.h)(&self.lazy[(k<<1)|1], &self.lazy[k]);
-        self.data[k] = self.reflect(k);
-        self.lazy[k] = self.o0;
+    fn recalc(&mut self, k: usize) {
+        self.data[k] = self.m.op(&self.data[2 * k], &self.data[2 * k + 1]);
     }
 
-    fn reflect(&self, k: usize) -> Monoid {
-        if self.lazy[k] == self.o0 {
-            self.data[k]
-        } else {
-            (self.g)(&self.data[k], &self.lazy[k])
+    fn reflect(&mut self, k: usize, f: &M::F) {
+        self.data[k] = self.m.mapping(f, &self.data[k]);
+        if k < self.size {
+            self.lazy[k] = self.m.composition(f, &self.lazy[k]);
         }
     }
 
-    fn recalc(&mut self, k: usize) {
-        let mut k = k;
-        while {
-            k >>= 1;
-            k > 0
-        } {
-            self.data[k] = (self.f)(&self.reflect((k<<1)|0), &self.reflect((k<<1)|1));
-        }
+    fn propagate(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.lazy[k], self.m.identity_map());
+        self.reflect(2 * k, &f);
+        self.reflect(2 * k + 1, &f);
     }
 
+    // Propagates every ancestor of the leaf `k`, top-down.
     fn thrust(&mut self, k: usize) {
         for i in (1..=self.height).rev() {
             self.propagate(k >> i);
         }
     }
 
-    fn update(&mut self, i: usize, j: usize, x: Operator) {
-        if i >= j { return }
-        let i = i + self.size;
-        let j = j + self.size - 1;
-        self.thrust(i);
-        self.thrust(j);
-        let (mut l, mut r) = (i, j + 1);
+    pub fn set(&mut self, p: usize, x: M::S) {
+        assert!(p < self.n);
+        let p = p + self.size;
+        self.thrust(p);
+        self.data[p] = x;
+        for i in 1..=self.height {
+            self.recalc(p >> i);
+        }
+    }
+
+    pub fn get(&mut self, p: usize) -> M::S {
+        assert!(p < self.n);
+        let p = p + self.size;
+        self.thrust(p);
+        self.data[p].clone()
+    }
+
+    /// `a[p] = f(a[p])`
+    pub fn apply(&mut self, p: usize, f: M::F) {
+        assert!(p < self.n);
+        let p = p + self.size;
+        self.thrust(p);
+        self.data[p] = self.m.mapping(&f, &self.data[p]);
+        for i in 1..=self.height {
+            self.recalc(p >> i);
+        }
+    }
+
+    /// `a[k] = f(a[k])` for `i <= k < j`
+    pub fn update(&mut self, i: usize, j: usize, f: M::F) {
+        assert!(i <= j && j <= self.n);
+        if i == j { return }
+        let (i, j) = (i + self.size, j + self.size);
+        for k in (1..=self.height).rev() {
+            if ((i >> k) << k) != i { self.propagate(i >> k); }
+            if ((j >> k) << k) != j { self.propagate((j - 1) >> k); }
+        }
+        let (mut l, mut r) = (i, j);
         while l < r {
             if l & 1 > 0 {
-                self.lazy[l] = (self.h)(&self.lazy[l], &x);
+                self.reflect(l, &f);
                 l += 1;
             }
             if r & 1 > 0 {
                 r -= 1;
-                self.lazy[r] = (self.h)(&self.lazy[r], &x);
+                self.reflect(r, &f);
             }
             l >>= 1;
             r >>= 1;
         }
-        self.recalc(i);
-        self.recalc(j);
+        for k in 1..=self.height {
+            if ((i >> k) << k) != i { self.recalc(i >> k); }
+            if ((j >> k) << k) != j { self.recalc((j - 1) >> k); }
+        }
     }
 
-    fn query(&mut self, i: usize, j: usize) -> Monoid {
-        if i >= j { return self.m0 }
-        let i = i + self.size;
-        let j = j + self.size - 1;
-        self.thrust(i);
-        self.thrust(j);
-        let (mut l, mut r) = (i, j + 1);    
-        let (mut vl, mut vr) = (self.m0, self.m0);
+    /// `a[i] * a[i+1] * ... * a[j-1]`
+    pub fn query(&mut self, i: usize, j: usize) -> M::S {
+        assert!(i <= j && j <= self.n);
+        if i == j { return self.m.identity() }
+        let (i, j) = (i + self.size, j + self.size);
+        for k in (1..=self.height).rev() {
+            if ((i >> k) << k) != i { self.propagate(i >> k); }
+            if ((j >> k) << k) != j { self.propagate((j - 1) >> k); }
+        }
+        let (mut l, mut r) = (i, j);
+        let (mut vl, mut vr) = (self.m.identity(), self.m.identity());
         while l < r {
             if l & 1 > 0 {
-                vl = (self.f)(&vl, &self.reflect(l));
+                vl = self.m.op(&vl, &self.data[l]);
                 l += 1;
             }
             if r & 1 > 0 {
                 r -= 1;
-                vr = (self.f)(&self.reflect(r), &vr);
+                vr = self.m.op(&self.data[r], &vr);
             }
             l >>= 1;
             r >>= 1;
         }
-        (self.f)(&vl, &vr)
+        self.m.op(&vl, &vr)
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.data[1].clone()
+    }
+
+    /// Maximum `r` such that `pred(query(l, r))` holds, assuming `pred` is monotone
+    /// and `pred(identity)` is true.
+    pub fn max_right<P>(&mut self, l: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(l <= self.n);
+        assert!(pred(&self.m.identity()));
+        if l == self.n { return self.n }
+        let mut l = l + self.size;
+        self.thrust(l);
+        let mut sm = self.m.identity();
+        loop {
+            while l & 1 == 0 { l >>= 1; }
+            let res = self.m.op(&sm, &self.data[l]);
+            if !pred(&res) {
+                while l < self.size {
+                    self.propagate(l);
+                    l <<= 1;
+                    let res = self.m.op(&sm, &self.data[l]);
+                    if pred(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = res;
+            l += 1;
+            if l.is_power_of_two() { break }
+        }
+        self.n
+    }
+
+    /// Minimum `l` such that `pred(query(l, r))` holds, assuming `pred` is monotone
+    /// and `pred(identity)` is true.
+    pub fn min_left<P>(&mut self, r: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(r <= self.n);
+        assert!(pred(&self.m.identity()));
+        if r == 0 { return 0 }
+        let mut r = r + self.size;
+        self.thrust(r - 1);
+        let mut sm = self.m.identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 { r >>= 1; }
+            let res = self.m.op(&self.data[r], &sm);
+            if !pred(&res) {
+                while r < self.size {
+                    self.propagate(r);
+                    r = 2 * r + 1;
+                    let res = self.m.op(&self.data[r], &sm);
+                    if pred(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = res;
+            if r.is_power_of_two() { break }
+        }
+        0
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
 
     #[test]
-    #[ignore]
     fn test_rsq_small() {
-        let mut rsq = LazySegTree::<i64, i64>::new(
-            3, |&a, &b| a + b, |&a, &b| a + b, |&a, &b| a + b, 0, 0
-        );
+        let mut rsq = LazySegTree::from_vec(vec![(0i64, 1i64); 3], RangeAddRangeSum::new());
         rsq.update(0, 0, 100);
-        assert_eq!(0, rsq.query(0, 2));
+        assert_eq!(0, rsq.query(0, 2).0);
         rsq.update(0, 1, 1);
-        assert_eq!(1, rsq.query(0, 3));
+        assert_eq!(1, rsq.query(0, 3).0);
         rsq.update(0, 2, 2);
-        assert_eq!(5, rsq.query(0, 3));
+        assert_eq!(5, rsq.query(0, 3).0);
         rsq.update(0, 3, 3);
         rsq.update(1, 2, 4);
         rsq.update(1, 3, 5);
         rsq.update(2, 3, 6);
         // 6 14 14
-        assert_eq!(6, rsq.query(0, 1));
-        assert_eq!(14, rsq.query(1, 2));
-        assert_eq!(14, rsq.query(2, 3));
-        assert_eq!(34, rsq.query(0, 3));
-        assert_eq!(28, rsq.query(1, 3));
+        assert_eq!(6, rsq.query(0, 1).0);
+        assert_eq!(14, rsq.query(1, 2).0);
+        assert_eq!(14, rsq.query(2, 3).0);
+        assert_eq!(34, rsq.query(0, 3).0);
+        assert_eq!(28, rsq.query(1, 3).0);
         rsq.update(1, 3, 5);
         // 6 19 19
-        assert_eq!(44, rsq.query(0, 3));
+        assert_eq!(44, rsq.query(0, 3).0);
         rsq.update(0, 2, 1);
         // 7 20 19
-        assert_eq!(46, rsq.query(0, 3));
-        assert_eq!(20, rsq.query(1, 2));
-        assert_eq!(39, rsq.query(1, 3));
+        assert_eq!(46, rsq.query(0, 3).0);
+        assert_eq!(20, rsq.query(1, 2).0);
+        assert_eq!(39, rsq.query(1, 3).0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_range_affine_range_sum() {
+        let mut rng = rand::thread_rng();
+        let n = 50;
+        let mut a: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(-1000..1000))).collect();
+        let mut seg = LazySegTree::from_vec(
+            a.iter().map(|&x| (x as i128, 1i128)).collect(),
+            RangeAffineRangeSum::new(),
+        );
+        for _ in 0..200 {
+            let l = rng.sample(Uniform::from(0..n));
+            let r = rng.sample(Uniform::from(l..=n));
+            match rng.sample(Uniform::from(0..4)) {
+                0 => {
+                    let b = rng.sample(Uniform::from(-3..=3i64));
+                    let c = rng.sample(Uniform::from(-100..=100i64));
+                    seg.update(l, r, (b as i128, c as i128));
+                    for x in &mut a[l..r] {
+                        *x = b * *x + c;
+                    }
+                }
+                1 if l < n => {
+                    let x = rng.sample(Uniform::from(-100..=100i64));
+                    seg.set(l, (x as i128, 1));
+                    a[l] = x;
+                }
+                2 if l < n => {
+                    seg.apply(l, (2, 1));
+                    a[l] = 2 * a[l] + 1;
+                    assert_eq!(seg.get(l), (a[l] as i128, 1));
+                }
+                _ => {
+                    let s: i64 = a[l..r].iter().sum();
+                    assert_eq!(seg.query(l, r), (s as i128, (r - l) as i128));
+                }
+            }
+            // reset before the values overflow
+            if a.iter().any(|x| x.abs() > 1_000_000_000_000) {
+                for (i, x) in a.iter_mut().enumerate() {
+                    *x %= 1000;
+                    seg.set(i, (*x as i128, 1));
+                }
+            }
+            assert_eq!(seg.all_prod().0, a.iter().sum::<i64>() as i128);
+        }
+    }
+
+    #[test]
+    fn test_range_chmin_range_max() {
+        let mut rng = rand::thread_rng();
+        let n = 40;
+        let mut a: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(-50..50))).collect();
+        let mut seg = LazySegTree::from_vec(a.clone(), RangeChminRangeMax::new());
+        for _ in 0..300 {
+            let l = rng.sample(Uniform::from(0..n));
+            let r = rng.sample(Uniform::from(l..=n));
+            if rng.sample(Uniform::from(0..2)) == 0 {
+                let x = rng.sample(Uniform::from(-50..50));
+                seg.update(l, r, x);
+                for v in &mut a[l..r] {
+                    *v = (*v).min(x);
+                }
+            } else {
+                let m = a[l..r].iter().copied().max().unwrap_or(i64::MIN);
+                assert_eq!(seg.query(l, r), m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_right_min_left_range_add_min() {
+        let mut rng = rand::thread_rng();
+        for n in 1..30 {
+            let mut a = vec![0i64; n];
+            let mut seg = LazySegTree::from_vec(a.clone(), RangeAddRangeMin::new());
+            for _ in 0..100 {
+                let l = rng.sample(Uniform::from(0..n));
+                let r = rng.sample(Uniform::from(l..=n));
+                let x = rng.sample(Uniform::from(-5..=5));
+                seg.update(l, r, x);
+                for v in &mut a[l..r] {
+                    *v += x;
+                }
+                let t = rng.sample(Uniform::from(-10..=10));
+                let l = rng.sample(Uniform::from(0..=n));
+                let mut expected = l;
+                while expected < n && a[expected] >= t {
+                    expected += 1;
+                }
+                assert_eq!(seg.max_right(l, |&m| m >= t), expected);
+                let r = rng.sample(Uniform::from(0..=n));
+                let mut expected = r;
+                while expected > 0 && a[expected - 1] >= t {
+                    expected -= 1;
+                }
+                assert_eq!(seg.min_left(r, |&m| m >= t), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,342 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("li_chao_tree")]
+/// Li Chao tree over a fixed set of query points `xs`:
+/// min (or max) of lines `y = a x + b` at a point, in `O(log n)`.
+///
+/// `add_line` is `O(log n)` and `add_segment` (a line restricted to `l <= x < r`) `O(log^2 n)`.
+pub struct LiChaoTree<T> {
+    // sorted distinct points, padded to `size` with the last one
+    xs: Vec<T>,
+    n: usize,
+    size: usize,
+    // lines of the min tree (max is stored negated)
+    line: Vec<Option<(T, T)>>,
+    minimize: bool,
+}
+
+#[snippet("li_chao_tree")]
+impl<T> LiChaoTree<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed,
+{
+    /// `xs` are the points that will be queried (any order, duplicates allowed).
+    pub fn new_min(xs: &[T]) -> Self {
+        Self::new(xs, true)
+    }
+
+    pub fn new_max(xs: &[T]) -> Self {
+        Self::new(xs, false)
+    }
+
+    fn new(xs: &[T], minimize: bool) -> Self {
+        let mut xs = xs.to_vec();
+        xs.sort();
+        xs.dedup();
+        let n = xs.len();
+        let mut size = 1;
+        while size < n { size <<= 1; }
+        if let Some(&last) = xs.last() {
+            xs.resize(size, last);
+        }
+        Self { xs, n, size, line: vec![None; 2 * size], minimize }
+    }
+
+    fn eval(f: (T, T), x: T) -> T {
+        f.0 * x + f.1
+    }
+
+    fn normalize(&self, a: T, b: T) -> (T, T) {
+        if self.minimize { (a, b) } else { (-a, -b) }
+    }
+
+    // Adds `f` to node `k` covering the points [l, r).
+    fn add_rec(&mut self, k: usize, l: usize, r: usize, f: (T, T)) {
+        let g = match self.line[k] {
+            None => {
+                self.line[k] = Some(f);
+                return;
+            }
+            Some(g) => g,
+        };
+        let m = (l + r) / 2;
+        let (lx, mx) = (self.xs[l], self.xs[m]);
+        let left_better = Self::eval(f, lx) < Self::eval(g, lx);
+        let mid_better = Self::eval(f, mx) < Self::eval(g, mx);
+        // the loser of the middle point goes down to the side where it may still win
+        let loser = if mid_better {
+            self.line[k] = Some(f);
+            g
+        } else {
+            f
+        };
+        if r - l == 1 { return }
+        if left_better != mid_better {
+            self.add_rec(2 * k, l, m, loser);
+        } else {
+            self.add_rec(2 * k + 1, m, r, loser);
+        }
+    }
+
+    pub fn add_line(&mut self, a: T, b: T) {
+        if self.n == 0 { return }
+        let f = self.normalize(a, b);
+        self.add_rec(1, 0, self.size, f);
+    }
+
+    /// Adds `y = a x + b` restricted to `l <= x < r`.
+    pub fn add_segment(&mut self, a: T, b: T, l: T, r: T) {
+        let f = self.normalize(a, b);
+        let li = self.xs[..self.n].partition_point(|&x| x < l);
+        let ri = self.xs[..self.n].partition_point(|&x| x < r);
+        let (mut lk, mut rk, mut w) = (li + self.size, ri + self.size, 1);
+        while lk < rk {
+            if lk & 1 == 1 {
+                self.add_rec(lk, lk * w - self.size, (lk + 1) * w - self.size, f);
+                lk += 1;
+            }
+            if rk & 1 == 1 {
+                rk -= 1;
+                self.add_rec(rk, rk * w - self.size, (rk + 1) * w - self.size, f);
+            }
+            lk >>= 1;
+            rk >>= 1;
+            w <<= 1;
+        }
+    }
+
+    /// Min (or max) at `x`, which must be one of the points given to the constructor;
+    /// `None` if no line covers `x`.
+    pub fn query(&self, x: T) -> Option<T> {
+        let i = self.xs[..self.n].binary_search(&x).expect("x is not a query point");
+        let mut k = i + self.size;
+        let mut res: Option<T> = None;
+        while k > 0 {
+            if let Some(f) = self.line[k] {
+                let y = Self::eval(f, x);
+                res = Some(res.map_or(y, |v| v.min(y)));
+            }
+            k >>= 1;
+        }
+        res.map(|y| if self.minimize { y } else { -y })
+    }
+}
+
+#[snippet("dynamic_li_chao_tree")]
+/// Li Chao tree over all integers `lo <= x < hi`, allocating nodes on demand.
+pub struct DynamicLiChaoTree<T> {
+    lo: T,
+    hi: T,
+    line: Vec<Option<(T, T)>>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    minimize: bool,
+}
+
+#[snippet("dynamic_li_chao_tree")]
+impl<T> DynamicLiChaoTree<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed,
+{
+    const NIL: usize = usize::MAX;
+
+    pub fn new_min(lo: T, hi: T) -> Self {
+        Self::new(lo, hi, true)
+    }
+
+    pub fn new_max(lo: T, hi: T) -> Self {
+        Self::new(lo, hi, false)
+    }
+
+    fn new(lo: T, hi: T, minimize: bool) -> Self {
+        assert!(lo < hi);
+        let mut t = Self { lo, hi, line: vec![], left: vec![], right: vec![], minimize };
+        t.new_node();
+        t
+    }
+
+    fn eval(f: (T, T), x: T) -> T {
+        f.0 * x + f.1
+    }
+
+    fn normalize(&self, a: T, b: T) -> (T, T) {
+        if self.minimize { (a, b) } else { (-a, -b) }
+    }
+
+    fn mid(l: T, r: T) -> T {
+        l + (r - l) / (T::one() + T::one())
+    }
+
+    fn new_node(&mut self) -> usize {
+        self.line.push(None);
+        self.left.push(Self::NIL);
+        self.right.push(Self::NIL);
+        self.line.len() - 1
+    }
+
+    fn child(&mut self, k: usize, go_left: bool) -> usize {
+        let c = if go_left { self.left[k] } else { self.right[k] };
+        if c != Self::NIL { return c }
+        let c = self.new_node();
+        if go_left { self.left[k] = c; } else { self.right[k] = c; }
+        c
+    }
+
+    // Adds `f` to node `k` covering [l, r).
+    fn add_rec(&mut self, k: usize, l: T, r: T, f: (T, T)) {
+        let g = match self.line[k] {
+            None => {
+                self.line[k] = Some(f);
+                return;
+            }
+            Some(g) => g,
+        };
+        let m = Self::mid(l, r);
+        let left_better = Self::eval(f, l) < Self::eval(g, l);
+        let mid_better = Self::eval(f, m) < Self::eval(g, m);
+        let loser = if mid_better {
+            self.line[k] = Some(f);
+            g
+        } else {
+            f
+        };
+        if r - l == T::one() { return }
+        if left_better != mid_better {
+            let c = self.child(k, true);
+            self.add_rec(c, l, m, loser);
+        } else {
+            let c = self.child(k, false);
+            self.add_rec(c, m, r, loser);
+        }
+    }
+
+    fn add_segment_rec(&mut self, k: usize, l: T, r: T, a: T, b: T, f: (T, T)) {
+        if b <= l || r <= a { return }
+        if a <= l && r <= b {
+            self.add_rec(k, l, r, f);
+            return;
+        }
+        let m = Self::mid(l, r);
+        if a < m {
+            let c = self.child(k, true);
+            self.add_segment_rec(c, l, m, a, b, f);
+        }
+        if m < b {
+            let c = self.child(k, false);
+            self.add_segment_rec(c, m, r, a, b, f);
+        }
+    }
+
+    pub fn add_line(&mut self, a: T, b: T) {
+        let f = self.normalize(a, b);
+        self.add_rec(0, self.lo, self.hi, f);
+    }
+
+    /// Adds `y = a x + b` restricted to `l <= x < r`.
+    pub fn add_segment(&mut self, a: T, b: T, l: T, r: T) {
+        let f = self.normalize(a, b);
+        self.add_segment_rec(0, self.lo, self.hi, l, r, f);
+    }
+
+    /// Min (or max) at `x` (`lo <= x < hi`); `None` if no line covers `x`.
+    pub fn query(&self, x: T) -> Option<T> {
+        assert!(self.lo <= x && x < self.hi);
+        let (mut k, mut l, mut r) = (0, self.lo, self.hi);
+        let mut res: Option<T> = None;
+        while k != Self::NIL {
+            if let Some(f) = self.line[k] {
+                let y = Self::eval(f, x);
+                res = Some(res.map_or(y, |v| v.min(y)));
+            }
+            let m = Self::mid(l, r);
+            if x < m {
+                k = self.left[k];
+                r = m;
+            } else {
+                k = self.right[k];
+                l = m;
+            }
+        }
+        res.map(|y| if self.minimize { y } else { -y })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    // (a, b, l, r): y = a x + b on l <= x < r
+    fn naive(segs: &[(i64, i64, i64, i64)], x: i64, minimize: bool) -> Option<i64> {
+        let ys = segs.iter().filter(|s| s.2 <= x && x < s.3).map(|s| s.0 * x + s.1);
+        if minimize { ys.min() } else { ys.max() }
+    }
+
+    #[test]
+    fn test_li_chao_tree() {
+        let mut rng = rand::thread_rng();
+        for &minimize in &[true, false] {
+            let xs: Vec<i64> = (0..30).map(|_| rng.sample(Uniform::from(-100..100))).collect();
+            let mut lct = if minimize { LiChaoTree::new_min(&xs) } else { LiChaoTree::new_max(&xs) };
+            let mut segs = vec![];
+            for _ in 0..100 {
+                let a = rng.sample(Uniform::from(-50..50));
+                let b = rng.sample(Uniform::from(-1000..1000));
+                if rng.sample(Uniform::from(0..2)) == 0 {
+                    lct.add_line(a, b);
+                    segs.push((a, b, i64::MIN, i64::MAX));
+                } else {
+                    let l = rng.sample(Uniform::from(-110..110));
+                    let r = rng.sample(Uniform::from(l..=110));
+                    lct.add_segment(a, b, l, r);
+                    segs.push((a, b, l, r));
+                }
+                for &x in &xs {
+                    assert_eq!(lct.query(x), naive(&segs, x, minimize));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_li_chao_tree() {
+        let mut rng = rand::thread_rng();
+        for &minimize in &[true, false] {
+            let (lo, hi) = (-60, 70);
+            let mut lct = if minimize {
+                DynamicLiChaoTree::new_min(lo, hi)
+            } else {
+                DynamicLiChaoTree::new_max(lo, hi)
+            };
+            let mut segs = vec![];
+            for _ in 0..100 {
+                let a = rng.sample(Uniform::from(-50..50));
+                let b = rng.sample(Uniform::from(-1000..1000));
+                if rng.sample(Uniform::from(0..2)) == 0 {
+                    lct.add_line(a, b);
+                    segs.push((a, b, i64::MIN, i64::MAX));
+                } else {
+                    let l = rng.sample(Uniform::from(lo - 10..hi + 10));
+                    let r = rng.sample(Uniform::from(l..=hi + 10));
+                    lct.add_segment(a, b, l, r);
+                    segs.push((a, b, l, r));
+                }
+                for x in lo..hi {
+                    assert_eq!(lct.query(x), naive(&segs, x, minimize));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_li_chao_tree_i128() {
+        let big = 1_000_000_000_000_000_000i128;
+        let mut lct = DynamicLiChaoTree::new_min(-big, big);
+        lct.add_line(big, 0);
+        lct.add_line(-big, 0);
+        assert_eq!(lct.query(big - 1), Some(-big * (big - 1)));
+        assert_eq!(lct.query(0), Some(0));
+    }
+}
@@ -0,0 +1,274 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("leftist_heap")]
+/// Min-heap that can `meld` two heaps in `O(log n)` (leftist heap); use `Reverse` for a max-heap.
+///
+/// The struct is an arena holding any number of heaps, each identified by its root handle
+/// (`NIL` is the empty heap). Operations take handles and return the new root, so a heap
+/// must not be used again after it was melded into another one.
+/// ```rust
+/// # use snippets::data_structure::meldable_heap::*;
+/// let nil = LeftistHeap::<i64>::NIL;
+/// let mut h = LeftistHeap::new();
+/// let a = h.push(nil, 5);
+/// let a = h.push(a, 1);
+/// let b = h.push(nil, 3);
+/// let c = h.meld(a, b);
+/// assert_eq!(h.top(c), Some(&1));
+/// let c = h.pop(c);
+/// assert_eq!(h.top(c), Some(&3));
+/// assert_eq!(h.len(c), 2);
+/// ```
+pub struct LeftistHeap<T> {
+    val: Vec<T>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    // length of the right spine
+    rank: Vec<u32>,
+    size: Vec<usize>,
+}
+
+#[snippet("leftist_heap")]
+impl<T> Default for LeftistHeap<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("leftist_heap")]
+impl<T> LeftistHeap<T>
+where
+    T: Ord,
+{
+    pub const NIL: usize = usize::MAX;
+
+    pub fn new() -> Self {
+        Self { val: vec![], left: vec![], right: vec![], rank: vec![], size: vec![] }
+    }
+
+    fn rank(&self, t: usize) -> u32 {
+        if t == Self::NIL { 0 } else { self.rank[t] }
+    }
+
+    /// Number of elements in heap `t`.
+    pub fn len(&self, t: usize) -> usize {
+        if t == Self::NIL { 0 } else { self.size[t] }
+    }
+
+    pub fn meld(&mut self, a: usize, b: usize) -> usize {
+        if a == Self::NIL { return b }
+        if b == Self::NIL { return a }
+        let (a, b) = if self.val[b] < self.val[a] { (b, a) } else { (a, b) };
+        self.right[a] = self.meld(self.right[a], b);
+        if self.rank(self.left[a]) < self.rank(self.right[a]) {
+            std::mem::swap(&mut self.left[a], &mut self.right[a]);
+        }
+        self.rank[a] = self.rank(self.right[a]) + 1;
+        self.size[a] = 1 + self.len(self.left[a]) + self.len(self.right[a]);
+        a
+    }
+
+    pub fn push(&mut self, t: usize, x: T) -> usize {
+        self.val.push(x);
+        self.left.push(Self::NIL);
+        self.right.push(Self::NIL);
+        self.rank.push(1);
+        self.size.push(1);
+        self.meld(t, self.val.len() - 1)
+    }
+
+    pub fn top(&self, t: usize) -> Option<&T> {
+        if t == Self::NIL { None } else { Some(&self.val[t]) }
+    }
+
+    /// Removes the minimum of heap `t` and returns the new root.
+    pub fn pop(&mut self, t: usize) -> usize {
+        if t == Self::NIL { return t }
+        self.meld(self.left[t], self.right[t])
+    }
+}
+
+#[snippet("lazy_leftist_heap")]
+/// `LeftistHeap` that can also add a constant to every element of a heap in `O(1)`.
+pub struct LazyLeftistHeap<T> {
+    val: Vec<T>,
+    // pending addition for the children of the node
+    lazy: Vec<T>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    rank: Vec<u32>,
+    size: Vec<usize>,
+}
+
+#[snippet("lazy_leftist_heap")]
+impl<T> Default for LazyLeftistHeap<T>
+where
+    T: Ord + Copy + std::ops::Add<Output = T> + num::traits::Zero,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("lazy_leftist_heap")]
+impl<T> LazyLeftistHeap<T>
+where
+    T: Ord + Copy + std::ops::Add<Output = T> + num::traits::Zero,
+{
+    pub const NIL: usize = usize::MAX;
+
+    pub fn new() -> Self {
+        Self { val: vec![], lazy: vec![], left: vec![], right: vec![], rank: vec![], size: vec![] }
+    }
+
+    fn rank(&self, t: usize) -> u32 {
+        if t == Self::NIL { 0 } else { self.rank[t] }
+    }
+
+    pub fn len(&self, t: usize) -> usize {
+        if t == Self::NIL { 0 } else { self.size[t] }
+    }
+
+    /// Adds `x` to every element of heap `t`.
+    pub fn add(&mut self, t: usize, x: T) {
+        if t == Self::NIL { return }
+        self.val[t] = self.val[t] + x;
+        self.lazy[t] = self.lazy[t] + x;
+    }
+
+    fn propagate(&mut self, t: usize) {
+        let x = self.lazy[t];
+        if x.is_zero() { return }
+        let (l, r) = (self.left[t], self.right[t]);
+        self.add(l, x);
+        self.add(r, x);
+        self.lazy[t] = T::zero();
+    }
+
+    pub fn meld(&mut self, a: usize, b: usize) -> usize {
+        if a == Self::NIL { return b }
+        if b == Self::NIL { return a }
+        let (a, b) = if self.val[b] < self.val[a] { (b, a) } else { (a, b) };
+        self.propagate(a);
+        self.right[a] = self.meld(self.right[a], b);
+        if self.rank(self.left[a]) < self.rank(self.right[a]) {
+            std::mem::swap(&mut self.left[a], &mut self.right[a]);
+        }
+        self.rank[a] = self.rank(self.right[a]) + 1;
+        self.size[a] = 1 + self.len(self.left[a]) + self.len(self.right[a]);
+        a
+    }
+
+    pub fn push(&mut self, t: usize, x: T) -> usize {
+        self.val.push(x);
+        self.lazy.push(T::zero());
+        self.left.push(Self::NIL);
+        self.right.push(Self::NIL);
+        self.rank.push(1);
+        self.size.push(1);
+        self.meld(t, self.val.len() - 1)
+    }
+
+    pub fn top(&self, t: usize) -> Option<T> {
+        if t == Self::NIL { None } else { Some(self.val[t]) }
+    }
+
+    /// Removes the minimum of heap `t` and returns the new root.
+    pub fn pop(&mut self, t: usize) -> usize {
+        if t == Self::NIL { return t }
+        self.propagate(t);
+        self.meld(self.left[t], self.right[t])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn test_leftist_heap() {
+        let mut rng = rand::thread_rng();
+        let k = 5;
+        let mut h = LeftistHeap::new();
+        let mut roots = vec![LeftistHeap::<i64>::NIL; k];
+        let mut naive: Vec<BinaryHeap<Reverse<i64>>> = vec![BinaryHeap::new(); k];
+        for _ in 0..3000 {
+            let i = rng.sample(Uniform::from(0..k));
+            match rng.sample(Uniform::from(0..4)) {
+                0 | 1 => {
+                    let x = rng.sample(Uniform::from(-100..100));
+                    roots[i] = h.push(roots[i], x);
+                    naive[i].push(Reverse(x));
+                }
+                2 => {
+                    roots[i] = h.pop(roots[i]);
+                    naive[i].pop();
+                }
+                _ => {
+                    let j = rng.sample(Uniform::from(0..k));
+                    if i != j {
+                        roots[i] = h.meld(roots[i], roots[j]);
+                        roots[j] = LeftistHeap::<i64>::NIL;
+                        let moved: Vec<_> = naive[j].drain().collect();
+                        naive[i].extend(moved);
+                    }
+                }
+            }
+            for i in 0..k {
+                assert_eq!(h.top(roots[i]).copied(), naive[i].peek().map(|x| x.0));
+                assert_eq!(h.len(roots[i]), naive[i].len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_lazy_leftist_heap() {
+        let mut rng = rand::thread_rng();
+        let k = 5;
+        let mut h = LazyLeftistHeap::new();
+        let mut roots = vec![LazyLeftistHeap::<i64>::NIL; k];
+        let mut naive: Vec<Vec<i64>> = vec![vec![]; k];
+        for _ in 0..3000 {
+            let i = rng.sample(Uniform::from(0..k));
+            match rng.sample(Uniform::from(0..5)) {
+                0 | 1 => {
+                    let x = rng.sample(Uniform::from(-100..100));
+                    roots[i] = h.push(roots[i], x);
+                    naive[i].push(x);
+                }
+                2 => {
+                    roots[i] = h.pop(roots[i]);
+                    if let Some(p) = (0..naive[i].len()).min_by_key(|&p| naive[i][p]) {
+                        naive[i].swap_remove(p);
+                    }
+                }
+                3 => {
+                    let x = rng.sample(Uniform::from(-10..10));
+                    h.add(roots[i], x);
+                    naive[i].iter_mut().for_each(|y| *y += x);
+                }
+                _ => {
+                    let j = rng.sample(Uniform::from(0..k));
+                    if i != j {
+                        roots[i] = h.meld(roots[i], roots[j]);
+                        roots[j] = LazyLeftistHeap::<i64>::NIL;
+                        let moved = std::mem::take(&mut naive[j]);
+                        naive[i].extend(moved);
+                    }
+                }
+            }
+            for i in 0..k {
+                assert_eq!(h.top(roots[i]), naive[i].iter().copied().min());
+                assert_eq!(h.len(roots[i]), naive[i].len());
+            }
+        }
+    }
+}
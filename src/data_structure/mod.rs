@@ -1,5 +1,21 @@
+pub mod monoid;
 pub mod unionfind;
+pub mod weighted_unionfind;
+pub mod rollback_unionfind;
 pub mod bit;
+pub mod bit2d;
+pub mod fenwick_multiset;
 pub mod segtree;
 pub mod lazy_segtree;
-pub mod btree;
\ No newline at end of file
+pub mod segtree_beats;
+pub mod persistent_segtree;
+pub mod dynamic_segtree;
+pub mod sparse_table;
+pub mod swag;
+pub mod wavelet_matrix;
+pub mod convex_hull_trick;
+pub mod li_chao_tree;
+pub mod btree;
+pub mod implicit_treap;
+pub mod meldable_heap;
+pub mod radix_heap;
\ No newline at end of file
@@ -0,0 +1,406 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::math::gcd::gcd;
+
+#[snippet("monoid")]
+/// An associative operation `op` with identity element `identity`.
+///
+/// Methods take `&self` so that a monoid may carry state (e.g. a closure, see `FnMonoid`).
+/// The ready-made monoids are zero-sized and built with `new()`.
+pub trait Monoid {
+    type S: Clone;
+    fn identity(&self) -> Self::S;
+    fn op(&self, a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+#[snippet("monoid")]
+/// Monoid from a closure and its identity element.
+///
+/// ```rust
+/// # use snippets::data_structure::monoid::*;
+/// let m = 1_000_000_007;
+/// let prod = FnMonoid::new(1i64, move |a: &i64, b: &i64| a * b % m);
+/// assert_eq!(prod.op(&prod.identity(), &5), 5);
+/// ```
+#[derive(Clone, Copy)]
+pub struct FnMonoid<T, F> {
+    id: T,
+    f: F,
+}
+
+#[snippet("monoid")]
+impl<T, F> FnMonoid<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    pub fn new(id: T, f: F) -> Self {
+        Self { id, f }
+    }
+}
+
+#[snippet("monoid")]
+impl<T, F> Monoid for FnMonoid<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    type S = T;
+    fn identity(&self) -> T {
+        self.id.clone()
+    }
+    fn op(&self, a: &T, b: &T) -> T {
+        (self.f)(a, b)
+    }
+}
+
+#[snippet("monoid")]
+macro_rules! marker_monoid {
+    ($($(#[$attr: meta])* $name: ident),*) => {
+        $(
+            $(#[$attr])*
+            pub struct $name<T>(std::marker::PhantomData<T>);
+
+            impl<T> $name<T> {
+                pub fn new() -> Self {
+                    $name(std::marker::PhantomData)
+                }
+            }
+
+            impl<T> Default for $name<T> {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl<T> Clone for $name<T> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<T> Copy for $name<T> {}
+
+            impl<T> std::fmt::Debug for $name<T> {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(f, stringify!($name))
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("monoid")]
+marker_monoid!(
+    /// `a + b`, identity `0`.
+    Sum,
+    /// `min(a, b)`, identity `T::max_value()`.
+    Min,
+    /// `max(a, b)`, identity `T::min_value()`.
+    Max,
+    /// `a ^ b`, identity `0`.
+    Xor,
+    /// `gcd(a, b)`, identity `0`.
+    Gcd,
+    /// Affine maps `x -> a x + b` stored as `(a, b)`.
+    /// `op(f, g)` is "apply `f`, then `g`", identity `(1, 0)`.
+    Affine
+);
+
+#[snippet("monoid")]
+impl<T: Clone + num::traits::Zero> Monoid for Sum<T> {
+    type S = T;
+    fn identity(&self) -> T {
+        T::zero()
+    }
+    fn op(&self, a: &T, b: &T) -> T {
+        a.clone() + b.clone()
+    }
+}
+
+#[snippet("monoid")]
+impl<T: Clone + Ord + num::traits::Bounded> Monoid for Min<T> {
+    type S = T;
+    fn identity(&self) -> T {
+        T::max_value()
+    }
+    fn op(&self, a: &T, b: &T) -> T {
+        a.min(b).clone()
+    }
+}
+
+#[snippet("monoid")]
+impl<T: Clone + Ord + num::traits::Bounded> Monoid for Max<T> {
+    type S = T;
+    fn identity(&self) -> T {
+        T::min_value()
+    }
+    fn op(&self, a: &T, b: &T) -> T {
+        a.max(b).clone()
+    }
+}
+
+#[snippet("monoid")]
+impl<T: Clone + num::traits::Zero + std::ops::BitXor<Output = T>> Monoid for Xor<T> {
+    type S = T;
+    fn identity(&self) -> T {
+        T::zero()
+    }
+    fn op(&self, a: &T, b: &T) -> T {
+        a.clone() ^ b.clone()
+    }
+}
+
+#[snippet("monoid", include = "gcd")]
+impl<T: num::traits::PrimInt> Monoid for Gcd<T> {
+    type S = T;
+    fn identity(&self) -> T {
+        T::zero()
+    }
+    fn op(&self, a: &T, b: &T) -> T {
+        gcd(*a, *b)
+    }
+}
+
+#[snippet("monoid")]
+impl<T: Clone + num::traits::Zero + num::traits::One> Monoid for Affine<T> {
+    type S = (T, T);
+    fn identity(&self) -> (T, T) {
+        (T::one(), T::zero())
+    }
+    fn op(&self, f: &(T, T), g: &(T, T)) -> (T, T) {
+        (
+            f.0.clone() * g.0.clone(),
+            f.1.clone() * g.0.clone() + g.1.clone(),
+        )
+    }
+}
+
+#[snippet("monoid")]
+/// A monoid whose elements have inverses (for `bit::BIT` and `bit2d::BIT2D`, which also need `op` to be commutative).
+pub trait Group: Monoid {
+    /// `a * b^{-1}`
+    fn inv_op(&self, a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+#[snippet("monoid")]
+impl<T: Clone + num::traits::Zero + std::ops::Sub<Output = T>> Group for Sum<T> {
+    fn inv_op(&self, a: &T, b: &T) -> T {
+        a.clone() - b.clone()
+    }
+}
+
+#[snippet("monoid")]
+impl<T: Clone + num::traits::Zero + std::ops::BitXor<Output = T>> Group for Xor<T> {
+    fn inv_op(&self, a: &T, b: &T) -> T {
+        a.clone() ^ b.clone()
+    }
+}
+
+#[snippet("monoid")]
+/// A monoid with `op(a, a) == a` (for `sparse_table::SparseTable`, whose ranges overlap).
+pub trait Idempotent: Monoid {}
+
+#[snippet("monoid")]
+impl<T: Clone + Ord + num::traits::Bounded> Idempotent for Min<T> {}
+#[snippet("monoid")]
+impl<T: Clone + Ord + num::traits::Bounded> Idempotent for Max<T> {}
+#[snippet("monoid", include = "gcd")]
+impl<T: num::traits::PrimInt> Idempotent for Gcd<T> {}
+
+#[snippet("map_monoid", include = "monoid")]
+/// A monoid acted on by maps `F` (for `lazy_segtree::LazySegTree`).
+///
+/// Requires `mapping(f, op(a, b)) == op(mapping(f, a), mapping(f, b))`
+/// and `mapping(composition(f, g), x) == mapping(f, mapping(g, x))`.
+pub trait MapMonoid: Monoid {
+    type F: Clone;
+    fn identity_map(&self) -> Self::F;
+    fn mapping(&self, f: &Self::F, x: &Self::S) -> Self::S;
+    /// `f ∘ g` (`g` is applied first), the reverse of `Affine::op`, whose left argument is applied first.
+    fn composition(&self, f: &Self::F, g: &Self::F) -> Self::F;
+}
+
+#[snippet("map_monoid", include = "monoid")]
+marker_monoid!(
+    /// Values `(sum, len)`, maps `x -> x + f`.
+    RangeAddRangeSum,
+    /// Values `(sum, len)`, maps `x -> a x + b` as `(a, b)`.
+    RangeAffineRangeSum,
+    /// Values `min`, maps `x -> x + f`.
+    RangeAddRangeMin,
+    /// Values `max`, maps `x -> x + f`.
+    RangeAddRangeMax,
+    /// Values `max`, maps `x -> min(x, f)`.
+    RangeChminRangeMax,
+    /// Values `min`, maps `x -> max(x, f)`.
+    RangeChmaxRangeMin
+);
+
+#[snippet("map_monoid", include = "monoid")]
+impl<T: Clone + num::traits::Zero> Monoid for RangeAddRangeSum<T> {
+    type S = (T, T);
+    fn identity(&self) -> (T, T) {
+        (T::zero(), T::zero())
+    }
+    fn op(&self, a: &(T, T), b: &(T, T)) -> (T, T) {
+        (a.0.clone() + b.0.clone(), a.1.clone() + b.1.clone())
+    }
+}
+
+#[snippet("map_monoid", include = "monoid")]
+impl<T: Clone + num::traits::Zero + num::traits::One> MapMonoid for RangeAddRangeSum<T> {
+    type F = T;
+    fn identity_map(&self) -> T {
+        T::zero()
+    }
+    fn mapping(&self, f: &T, x: &(T, T)) -> (T, T) {
+        (x.0.clone() + f.clone() * x.1.clone(), x.1.clone())
+    }
+    fn composition(&self, f: &T, g: &T) -> T {
+        f.clone() + g.clone()
+    }
+}
+
+#[snippet("map_monoid", include = "monoid")]
+impl<T: Clone + num::traits::Zero> Monoid for RangeAffineRangeSum<T> {
+    type S = (T, T);
+    fn identity(&self) -> (T, T) {
+        (T::zero(), T::zero())
+    }
+    fn op(&self, a: &(T, T), b: &(T, T)) -> (T, T) {
+        (a.0.clone() + b.0.clone(), a.1.clone() + b.1.clone())
+    }
+}
+
+#[snippet("map_monoid", include = "monoid")]
+impl<T: Clone + num::traits::Zero + num::traits::One> MapMonoid for RangeAffineRangeSum<T> {
+    type F = (T, T);
+    fn identity_map(&self) -> (T, T) {
+        (T::one(), T::zero())
+    }
+    fn mapping(&self, f: &(T, T), x: &(T, T)) -> (T, T) {
+        (f.0.clone() * x.0.clone() + f.1.clone() * x.1.clone(), x.1.clone())
+    }
+    fn composition(&self, f: &(T, T), g: &(T, T)) -> (T, T) {
+        (f.0.clone() * g.0.clone(), f.0.clone() * g.1.clone() + f.1.clone())
+    }
+}
+
+#[snippet("map_monoid", include = "monoid")]
+macro_rules! impl_bounded_map_monoid {
+    (
+        $name: ident, $identity: ident, $op: ident, $id_map: ident,
+        |$f: ident, $x: ident| $mapping: expr,
+        |$g: ident, $h: ident| $composition: expr
+    ) => {
+        impl<T: Clone + Ord + num::traits::Bounded + num::traits::Zero> Monoid for $name<T> {
+            type S = T;
+            fn identity(&self) -> T {
+                T::$identity()
+            }
+            fn op(&self, a: &T, b: &T) -> T {
+                a.$op(b).clone()
+            }
+        }
+
+        impl<T: Clone + Ord + num::traits::Bounded + num::traits::Zero> MapMonoid for $name<T> {
+            type F = T;
+            fn identity_map(&self) -> T {
+                T::$id_map()
+            }
+            fn mapping(&self, $f: &T, $x: &T) -> T {
+                $mapping
+            }
+            fn composition(&self, $g: &T, $h: &T) -> T {
+                $composition
+            }
+        }
+    };
+}
+
+// The identity element (`T::max_value()` for min) is left untouched so that it cannot overflow.
+#[snippet("map_monoid", include = "monoid")]
+impl_bounded_map_monoid!(
+    RangeAddRangeMin, max_value, min, zero,
+    |f, x| if *x == T::max_value() { x.clone() } else { x.clone() + f.clone() },
+    |f, g| f.clone() + g.clone()
+);
+#[snippet("map_monoid", include = "monoid")]
+impl_bounded_map_monoid!(
+    RangeAddRangeMax, min_value, max, zero,
+    |f, x| if *x == T::min_value() { x.clone() } else { x.clone() + f.clone() },
+    |f, g| f.clone() + g.clone()
+);
+#[snippet("map_monoid", include = "monoid")]
+impl_bounded_map_monoid!(
+    RangeChminRangeMax, min_value, max, max_value,
+    |f, x| f.min(x).clone(),
+    |f, g| f.min(g).clone()
+);
+#[snippet("map_monoid", include = "monoid")]
+impl_bounded_map_monoid!(
+    RangeChmaxRangeMin, max_value, min, min_value,
+    |f, x| f.max(x).clone(),
+    |f, g| f.max(g).clone()
+);
+
+#[snippet("map_monoid", include = "monoid")]
+/// A plain monoid as a `MapMonoid` whose only map is the identity
+/// (e.g. `implicit_treap::ImplicitTreap` with `reverse` but no range updates).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoMap<M>(pub M);
+
+#[snippet("map_monoid", include = "monoid")]
+impl<M: Monoid> Monoid for NoMap<M> {
+    type S = M::S;
+    fn identity(&self) -> M::S {
+        self.0.identity()
+    }
+    fn op(&self, a: &M::S, b: &M::S) -> M::S {
+        self.0.op(a, b)
+    }
+}
+
+#[snippet("map_monoid", include = "monoid")]
+impl<M: Monoid> MapMonoid for NoMap<M> {
+    type F = ();
+    fn identity_map(&self) {}
+    fn mapping(&self, _: &(), x: &M::S) -> M::S {
+        x.clone()
+    }
+    fn composition(&self, _: &(), _: &()) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fold<M: Monoid>(m: &M, v: &[M::S]) -> M::S {
+        v.iter().fold(m.identity(), |acc, x| m.op(&acc, x))
+    }
+
+    #[test]
+    fn test_ready_made_monoids() {
+        let v = vec![12i64, -3, 18, 7];
+        assert_eq!(fold(&Sum::new(), &v), 34);
+        assert_eq!(fold(&Min::new(), &v), -3);
+        assert_eq!(fold(&Max::new(), &v), 18);
+        assert_eq!(fold(&Xor::new(), &v), 12 ^ -3 ^ 18 ^ 7);
+        assert_eq!(fold(&Gcd::new(), &[12i64, 18, 42]), 6);
+        assert_eq!(fold(&Min::<i64>::new(), &[]), i64::MAX);
+    }
+
+    #[test]
+    fn test_affine_monoid() {
+        let m = Affine::<i64>::new();
+        // x -> 2x + 1, then x -> 3x + 4
+        let h = m.op(&(2, 1), &(3, 4));
+        assert_eq!(h, (6, 7));
+        assert_eq!(m.op(&m.identity(), &h), h);
+        assert_eq!(m.op(&h, &m.identity()), h);
+        // `composition` takes the maps in the opposite order
+        let mm = RangeAffineRangeSum::<i64>::new();
+        assert_eq!(mm.composition(&(3, 4), &(2, 1)), h);
+    }
+}
@@ -0,0 +1,269 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::{Monoid, Sum};
+
+#[snippet("persistent_segtree", include = "monoid")]
+/// Persistent segment tree (path copying) over a `Monoid`.
+///
+/// Versions are numbered from `0` (the initial array) and every `update`
+/// creates a new version in `O(log n)` nodes; old versions stay queryable.
+pub struct PersistentSegTree<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    size: usize,
+    val: Vec<M::S>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    roots: Vec<usize>,
+    m: M,
+}
+
+#[snippet("persistent_segtree", include = "monoid")]
+impl<M> PersistentSegTree<M>
+where
+    M: Monoid,
+{
+    const NIL: usize = usize::MAX;
+
+    pub fn new(n: usize, m: M) -> Self {
+        Self::from_vec(vec![m.identity(); n], m)
+    }
+
+    pub fn from_vec(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
+        let mut size = 1;
+        while size < n { size <<= 1; }
+        let mut seg = Self {
+            n,
+            size,
+            val: Vec::with_capacity(2 * size),
+            left: Vec::with_capacity(2 * size),
+            right: Vec::with_capacity(2 * size),
+            roots: vec![],
+            m,
+        };
+        let root = seg.build(&v, 0, size);
+        seg.roots.push(root);
+        seg
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Number of versions created so far (including the initial one).
+    pub fn versions(&self) -> usize {
+        self.roots.len()
+    }
+
+    fn push(&mut self, x: M::S, l: usize, r: usize) -> usize {
+        self.val.push(x);
+        self.left.push(l);
+        self.right.push(r);
+        self.val.len() - 1
+    }
+
+    fn build(&mut self, v: &[M::S], l: usize, r: usize) -> usize {
+        if r - l == 1 {
+            let x = if l < v.len() { v[l].clone() } else { self.m.identity() };
+            return self.push(x, Self::NIL, Self::NIL);
+        }
+        let mid = (l + r) / 2;
+        let lc = self.build(v, l, mid);
+        let rc = self.build(v, mid, r);
+        let x = self.m.op(&self.val[lc], &self.val[rc]);
+        self.push(x, lc, rc)
+    }
+
+    fn set_rec(&mut self, node: usize, l: usize, r: usize, k: usize, x: M::S) -> usize {
+        if r - l == 1 {
+            return self.push(x, Self::NIL, Self::NIL);
+        }
+        let mid = (l + r) / 2;
+        let (mut lc, mut rc) = (self.left[node], self.right[node]);
+        if k < mid {
+            lc = self.set_rec(lc, l, mid, k, x);
+        } else {
+            rc = self.set_rec(rc, mid, r, k, x);
+        }
+        let y = self.m.op(&self.val[lc], &self.val[rc]);
+        self.push(y, lc, rc)
+    }
+
+    fn query_rec(&self, node: usize, l: usize, r: usize, a: usize, b: usize) -> M::S {
+        if b <= l || r <= a { return self.m.identity() }
+        if a <= l && r <= b { return self.val[node].clone() }
+        let mid = (l + r) / 2;
+        self.m.op(
+            &self.query_rec(self.left[node], l, mid, a, b),
+            &self.query_rec(self.right[node], mid, r, a, b),
+        )
+    }
+
+    /// Creates a new version from `ver` with `a[k] = x` and returns its number.
+    pub fn update(&mut self, ver: usize, k: usize, x: M::S) -> usize {
+        assert!(k < self.n);
+        let root = self.set_rec(self.roots[ver], 0, self.size, k, x);
+        self.roots.push(root);
+        self.roots.len() - 1
+    }
+
+    pub fn get(&self, ver: usize, k: usize) -> &M::S {
+        assert!(k < self.n);
+        let (mut node, mut l, mut r) = (self.roots[ver], 0, self.size);
+        while r - l > 1 {
+            let mid = (l + r) / 2;
+            if k < mid {
+                node = self.left[node];
+                r = mid;
+            } else {
+                node = self.right[node];
+                l = mid;
+            }
+        }
+        &self.val[node]
+    }
+
+    /// `a[i] * a[i+1] * ... * a[j-1]` in version `ver`
+    pub fn query(&self, ver: usize, i: usize, j: usize) -> M::S {
+        assert!(i <= j && j <= self.n);
+        self.query_rec(self.roots[ver], 0, self.size, i, j)
+    }
+
+    pub fn all_prod(&self, ver: usize) -> M::S {
+        self.val[self.roots[ver]].clone()
+    }
+}
+
+#[snippet("persistent_segtree_kth", include = "persistent_segtree")]
+/// K-th smallest value in `a[l..r]`, in `O(log n)` per query.
+///
+/// Version `i` of the inner tree counts the (compressed) values of the prefix `a[..i]`,
+/// so the counts of `a[l..r]` are the difference of versions `r` and `l`.
+pub struct RangeKth<T> {
+    xs: Vec<T>,
+    seg: PersistentSegTree<Sum<usize>>,
+}
+
+#[snippet("persistent_segtree_kth", include = "persistent_segtree")]
+impl<T> RangeKth<T>
+where
+    T: Clone + Ord,
+{
+    pub fn new(a: &[T]) -> Self {
+        let mut xs = a.to_vec();
+        xs.sort();
+        xs.dedup();
+        let mut seg = PersistentSegTree::new(xs.len(), Sum::new());
+        for (i, x) in a.iter().enumerate() {
+            let k = xs.binary_search(x).unwrap();
+            let c = *seg.get(i, k);
+            seg.update(i, k, c + 1);
+        }
+        Self { xs, seg }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seg.versions() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `k`-th (0-indexed) smallest value of `a[l..r]`, or `None` if `k >= r - l`.
+    pub fn kth_smallest(&self, l: usize, r: usize, k: usize) -> Option<T> {
+        assert!(l <= r && r <= self.len());
+        if k >= r - l { return None }
+        let seg = &self.seg;
+        let (mut u, mut v) = (seg.roots[l], seg.roots[r]);
+        let (mut k, mut lo, mut hi) = (k, 0, seg.size);
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            let c = seg.val[seg.left[v]] - seg.val[seg.left[u]];
+            if k < c {
+                u = seg.left[u];
+                v = seg.left[v];
+                hi = mid;
+            } else {
+                k -= c;
+                u = seg.right[u];
+                v = seg.right[v];
+                lo = mid;
+            }
+        }
+        Some(self.xs[lo].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_persistent_segtree_versions() {
+        let mut seg = PersistentSegTree::from_vec(vec![1i64, 2, 3, 4, 5], Sum::new());
+        let v1 = seg.update(0, 2, 10);
+        let v2 = seg.update(v1, 0, 0);
+        let v3 = seg.update(0, 4, -5);
+        assert_eq!(seg.all_prod(0), 15);
+        assert_eq!(seg.all_prod(v1), 22);
+        assert_eq!(seg.query(v2, 0, 3), 12);
+        assert_eq!(seg.query(v3, 3, 5), -1);
+        assert_eq!(seg.get(v3, 2), &3);
+        assert_eq!(seg.versions(), 4);
+    }
+
+    #[test]
+    fn test_persistent_segtree_random() {
+        let mut rng = rand::thread_rng();
+        for n in 1..20 {
+            let a: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(-100..100))).collect();
+            let mut hist = vec![a.clone()];
+            let mut seg = PersistentSegTree::from_vec(a, Min::new());
+            for _ in 0..100 {
+                let ver = rng.sample(Uniform::from(0..hist.len()));
+                let k = rng.sample(Uniform::from(0..n));
+                let x = rng.sample(Uniform::from(-100..100));
+                let mut b = hist[ver].clone();
+                b[k] = x;
+                hist.push(b);
+                assert_eq!(seg.update(ver, k, x), hist.len() - 1);
+
+                let ver = rng.sample(Uniform::from(0..hist.len()));
+                let l = rng.sample(Uniform::from(0..=n));
+                let r = rng.sample(Uniform::from(l..=n));
+                let expected = hist[ver][l..r].iter().copied().min().unwrap_or(i64::MAX);
+                assert_eq!(seg.query(ver, l, r), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_kth() {
+        let mut rng = rand::thread_rng();
+        for n in 0..30 {
+            let a: Vec<i32> = (0..n).map(|_| rng.sample(Uniform::from(-20..20))).collect();
+            let rk = RangeKth::new(&a);
+            assert_eq!(rk.len(), n);
+            for l in 0..=n {
+                for r in l..=n {
+                    let mut b = a[l..r].to_vec();
+                    b.sort();
+                    for k in 0..=b.len() {
+                        assert_eq!(rk.kth_smallest(l, r, k), b.get(k).copied());
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("radix_heap")]
+/// Min-heap on `u64` keys that never go below the last popped key (e.g. Dijkstra distances).
+///
+/// Bucket `i` holds the keys whose highest bit differing from `last` is bit `i - 1`;
+/// every element moves to a lower bucket at most 64 times, so `pop` is amortized `O(log C)`.
+pub struct RadixHeap<V> {
+    buckets: Vec<Vec<(u64, V)>>,
+    last: u64,
+    len: usize,
+}
+
+#[snippet("radix_heap")]
+impl<V> Default for RadixHeap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("radix_heap")]
+impl<V> RadixHeap<V> {
+    pub fn new() -> Self {
+        Self { buckets: (0..65).map(|_| vec![]).collect(), last: 0, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn bucket(&self, key: u64) -> usize {
+        (64 - (key ^ self.last).leading_zeros()) as usize
+    }
+
+    /// Panics if `key` is smaller than the last popped key.
+    pub fn push(&mut self, key: u64, value: V) {
+        assert!(key >= self.last, "keys must not go below the last popped key");
+        let b = self.bucket(key);
+        self.buckets[b].push((key, value));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 { return None }
+        if self.buckets[0].is_empty() {
+            let i = (1..65).find(|&i| !self.buckets[i].is_empty()).unwrap();
+            let items = std::mem::take(&mut self.buckets[i]);
+            self.last = items.iter().map(|&(k, _)| k).min().unwrap();
+            for (k, v) in items {
+                let b = self.bucket(k);
+                self.buckets[b].push((k, v));
+            }
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn test_radix_heap() {
+        let mut rng = rand::thread_rng();
+        let mut h = RadixHeap::new();
+        let mut naive = BinaryHeap::new();
+        let mut last = 0;
+        for i in 0..5000 {
+            if rng.sample(Uniform::from(0..3)) == 0 {
+                let p = h.pop();
+                assert_eq!(p.map(|(k, _)| k), naive.pop().map(|Reverse(k)| k));
+                if let Some((k, _)) = p {
+                    last = k;
+                }
+            } else {
+                let bits = rng.sample(Uniform::from(0..40));
+                let key = last + rng.sample(Uniform::from(0..1u64 << bits));
+                h.push(key, i);
+                naive.push(Reverse(key));
+            }
+            assert_eq!(h.len(), naive.len());
+        }
+    }
+
+    #[test]
+    fn test_radix_heap_dijkstra() {
+        let mut rng = rand::thread_rng();
+        let n = 200;
+        let mut g = vec![vec![]; n];
+        for _ in 0..1000 {
+            let u = rng.sample(Uniform::from(0..n));
+            let v = rng.sample(Uniform::from(0..n));
+            g[u].push((v, rng.sample(Uniform::from(0..1_000_000_000u64))));
+        }
+        let inf = u64::MAX;
+        let mut dist = vec![inf; n];
+        let mut h = RadixHeap::new();
+        dist[0] = 0;
+        h.push(0, 0);
+        while let Some((d, u)) = h.pop() {
+            if d > dist[u] { continue }
+            for &(v, w) in &g[u] {
+                if d + w < dist[v] {
+                    dist[v] = d + w;
+                    h.push(d + w, v);
+                }
+            }
+        }
+        let mut expected = vec![inf; n];
+        let mut pq = BinaryHeap::new();
+        expected[0] = 0;
+        pq.push(Reverse((0, 0)));
+        while let Some(Reverse((d, u))) = pq.pop() {
+            if d > expected[u] { continue }
+            for &(v, w) in &g[u] {
+                if d + w < expected[v] {
+                    expected[v] = d + w;
+                    pq.push(Reverse((d + w, v)));
+                }
+            }
+        }
+        assert_eq!(dist, expected);
+    }
+}
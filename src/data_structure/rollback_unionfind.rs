@@ -0,0 +1,236 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("rollback_unionfind")]
+/// Union-find with undo: union by size and no path compression, `O(log n)` per operation.
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    sizes: Vec<usize>,
+    size: usize,
+    // (attached root, new parent) for every `unite` call; `None` if it merged nothing
+    history: Vec<Option<(usize, usize)>>,
+}
+
+#[snippet("rollback_unionfind")]
+impl RollbackUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            sizes: vec![1; n],
+            size: n,
+            history: vec![],
+        }
+    }
+
+    pub fn find(&self, x: usize) -> usize {
+        let mut x = x;
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        let (px, py) = (self.find(x), self.find(y));
+        if px == py {
+            self.history.push(None);
+            return false;
+        }
+        let (large, small) = if self.sizes[px] >= self.sizes[py] { (px, py) } else { (py, px) };
+        self.parent[small] = large;
+        self.sizes[large] += self.sizes[small];
+        self.size -= 1;
+        self.history.push(Some((small, large)));
+        true
+    }
+
+    pub fn same(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.sizes[self.find(x)]
+    }
+
+    /// Number of components
+    pub fn count(&self) -> usize {
+        self.size
+    }
+
+    /// Undoes the last `unite` call (whether or not it merged anything).
+    pub fn undo(&mut self) {
+        if let Some((small, large)) = self.history.pop().expect("nothing to undo") {
+            self.parent[small] = small;
+            self.sizes[large] -= self.sizes[small];
+            self.size += 1;
+        }
+    }
+
+    /// A point to come back to with `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes every `unite` since `snapshot()` returned `snap`.
+    pub fn rollback(&mut self, snap: usize) {
+        while self.history.len() > snap {
+            self.undo();
+        }
+    }
+}
+
+#[snippet("dynamic_connectivity", include = "rollback_unionfind")]
+/// Offline dynamic connectivity: edge insertions / deletions and connectivity queries,
+/// answered all at once in `O(q log q log n)`.
+///
+/// Each edge is alive on an interval of query indices; the intervals are put on a segment
+/// tree over the queries, which is walked with a `RollbackUnionFind`.
+pub struct OfflineDynamicConnectivity {
+    n: usize,
+    queries: Vec<(usize, usize)>,
+    // edge -> start (query index) of each live copy
+    open: std::collections::HashMap<(usize, usize), Vec<usize>>,
+    // (u, v, l, r): the edge is alive for the queries l..r
+    spans: Vec<(usize, usize, usize, usize)>,
+}
+
+#[snippet("dynamic_connectivity", include = "rollback_unionfind")]
+impl OfflineDynamicConnectivity {
+    pub fn new(n: usize) -> Self {
+        Self { n, queries: vec![], open: std::collections::HashMap::new(), spans: vec![] }
+    }
+
+    fn key(u: usize, v: usize) -> (usize, usize) {
+        (u.min(v), u.max(v))
+    }
+
+    /// Adds an (undirected) edge; multi-edges are allowed.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.n && v < self.n);
+        let t = self.queries.len();
+        self.open.entry(Self::key(u, v)).or_default().push(t);
+    }
+
+    /// Removes one copy of the edge, which must exist.
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        let (a, b) = Self::key(u, v);
+        let l = self.open.get_mut(&(a, b)).and_then(|s| s.pop()).expect("no such edge");
+        self.spans.push((a, b, l, self.queries.len()));
+    }
+
+    /// Asks whether `u` and `v` are connected at this point.
+    pub fn query(&mut self, u: usize, v: usize) {
+        assert!(u < self.n && v < self.n);
+        self.queries.push((u, v));
+    }
+
+    /// Answers to the queries, in order.
+    pub fn solve(&self) -> Vec<bool> {
+        let q = self.queries.len();
+        let mut size = 1;
+        while size < q { size <<= 1; }
+        let mut seg: Vec<Vec<(usize, usize)>> = vec![vec![]; 2 * size];
+        let still_open = self.open.iter()
+            .flat_map(|(&(a, b), s)| s.iter().map(move |&l| (a, b, l, q)));
+        for (a, b, l, r) in self.spans.iter().copied().chain(still_open) {
+            let (mut l, mut r) = (l + size, r + size);
+            while l < r {
+                if l & 1 == 1 {
+                    seg[l].push((a, b));
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    seg[r].push((a, b));
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+        let mut uf = RollbackUnionFind::new(self.n);
+        let mut ans = vec![false; q];
+        self.dfs(1, size, &seg, &mut uf, &mut ans);
+        ans
+    }
+
+    fn dfs(
+        &self,
+        k: usize,
+        size: usize,
+        seg: &[Vec<(usize, usize)>],
+        uf: &mut RollbackUnionFind,
+        ans: &mut [bool],
+    ) {
+        let snap = uf.snapshot();
+        for &(a, b) in &seg[k] {
+            uf.unite(a, b);
+        }
+        if k >= size {
+            if let Some(&(u, v)) = self.queries.get(k - size) {
+                ans[k - size] = uf.same(u, v);
+            }
+        } else {
+            self.dfs(2 * k, size, seg, uf, ans);
+            self.dfs(2 * k + 1, size, seg, uf, ans);
+        }
+        uf.rollback(snap);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_rollback_unionfind() {
+        let mut uf = RollbackUnionFind::new(5);
+        uf.unite(0, 1);
+        let snap = uf.snapshot();
+        uf.unite(1, 2);
+        uf.unite(0, 2);
+        uf.unite(3, 4);
+        assert!(uf.same(0, 2));
+        assert_eq!(uf.count(), 2);
+        uf.undo();
+        assert!(!uf.same(3, 4));
+        uf.rollback(snap);
+        assert!(uf.same(0, 1));
+        assert!(!uf.same(0, 2));
+        assert_eq!(uf.size(1), 2);
+        assert_eq!(uf.count(), 4);
+    }
+
+    #[test]
+    fn test_offline_dynamic_connectivity() {
+        let mut rng = rand::thread_rng();
+        let n = 8;
+        let mut dc = OfflineDynamicConnectivity::new(n);
+        let mut edges: Vec<(usize, usize)> = vec![];
+        let mut expected = vec![];
+        for _ in 0..300 {
+            let u = rng.sample(Uniform::from(0..n));
+            let v = rng.sample(Uniform::from(0..n));
+            match rng.sample(Uniform::from(0..3)) {
+                0 => {
+                    dc.add_edge(u, v);
+                    edges.push((u, v));
+                }
+                1 if !edges.is_empty() => {
+                    let (a, b) = edges.swap_remove(rng.sample(Uniform::from(0..edges.len())));
+                    dc.remove_edge(b, a);
+                }
+                _ => {
+                    let mut uf = RollbackUnionFind::new(n);
+                    for &(a, b) in &edges {
+                        uf.unite(a, b);
+                    }
+                    expected.push(uf.same(u, v));
+                    dc.query(u, v);
+                }
+            }
+        }
+        assert_eq!(dc.solve(), expected);
+    }
+}
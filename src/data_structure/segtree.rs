@@ -1,22 +1,24 @@
 #![allow(dead_code)]
 use cargo_snippet::snippet;
+use crate::data_structure::monoid::Monoid;
 
 
-#[snippet("segtree")]
-struct SegTree<T>
+#[snippet("segtree", include = "monoid")]
+pub struct SegTree<M>
 where
-    T: Copy,
+    M: Monoid,
 {
+    n: usize,
     size: usize,
-    seg: Vec<T>,
-    f: fn(&T, &T) -> T,
-    id: T,
+    seg: Vec<M::S>,
+    m: M,
 }
 
-#[snippet("segtree")]
-impl<T> std::fmt::Debug for SegTree<T>
+#[snippet("segtree", include = "monoid")]
+impl<M> std::fmt::Debug for SegTree<M>
 where
-    T: Copy + std::fmt::Debug
+    M: Monoid,
+    M::S: std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "SegTree {{ ")?;
@@ -25,69 +27,160 @@ where
     }
 }
 
-#[snippet("segtree")]
-impl<T> SegTree<T>
+#[snippet("segtree", include = "monoid")]
+impl<M> SegTree<M>
 where
-    T: Copy,
+    M: Monoid,
 {
-    fn new(n: usize, f: fn(&T, &T) -> T, id: T) -> SegTree<T> {
+    pub fn new(n: usize, m: M) -> SegTree<M> {
         let mut size = 1;
         while n > size { size <<= 1; }
-        let seg = vec![id; 2*size];
-        Self { size, seg, f, id }
+        let seg = vec![m.identity(); 2*size];
+        Self { n, size, seg, m }
     }
 
-    fn set(&mut self, k: usize, v: T) {
+    pub fn from_vec(v: Vec<M::S>, m: M) -> SegTree<M> {
+        let mut seg = Self::new(v.len(), m);
+        for (k, x) in v.into_iter().enumerate() {
+            seg.set(k, x);
+        }
+        seg.build();
+        seg
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Sets `a[k] = v` without updating the ancestors. Call `build` afterwards.
+    pub fn set(&mut self, k: usize, v: M::S) {
         self.seg[k + self.size] = v;
     }
 
-    fn get(&self, k: usize) -> T {
-        self.seg[k + self.size]
+    pub fn get(&self, k: usize) -> &M::S {
+        &self.seg[k + self.size]
     }
 
-    fn build(&mut self) {
+    pub fn build(&mut self) {
         for k in (1..self.size).rev() {
-            self.seg[k] = (self.f)(&self.seg[2 * k + 0], &self.seg[2 * k + 1]);
+            self.seg[k] = self.m.op(&self.seg[2 * k], &self.seg[2 * k + 1]);
         }
     }
 
-    fn update(&mut self, k: usize, v: T) {
+    pub fn update(&mut self, k: usize, v: M::S) {
         let mut k = k + self.size;
         self.seg[k] = v;
         while k > 1 {
-            self.seg[k >> 1] = (self.f)(&self.seg[k], &self.seg[k^1]);
             k >>= 1;
+            self.seg[k] = self.m.op(&self.seg[2 * k], &self.seg[2 * k + 1]);
         }
     }
 
-    fn query(&self, i: usize, j: usize) -> T {
-        let mut s = self.id;
+    /// `a[i] * a[i+1] * ... * a[j-1]`
+    pub fn query(&self, i: usize, j: usize) -> M::S {
+        let mut sl = self.m.identity();
+        let mut sr = self.m.identity();
         let mut l = i + self.size;
         let mut r = j + self.size;
         while l < r {
             if (l & 1) > 0 {
-                s = (self.f)(&s, &self.seg[l]);
+                sl = self.m.op(&sl, &self.seg[l]);
                 l += 1;
             }
-            if (r & 1) > 0{
-                s = (self.f)(&s, &self.seg[r - 1]);
+            if (r & 1) > 0 {
+                r -= 1;
+                sr = self.m.op(&self.seg[r], &sr);
             }
             l >>= 1;
             r >>= 1;
         }
-        s
+        self.m.op(&sl, &sr)
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.seg[1].clone()
+    }
+
+    /// Maximum `r` such that `pred(query(l, r))` holds, assuming `pred` is monotone
+    /// and `pred(identity)` is true. Runs in `O(log n)`.
+    pub fn max_right<P>(&self, l: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(l <= self.n);
+        assert!(pred(&self.m.identity()));
+        if l == self.n { return self.n }
+        let mut l = l + self.size;
+        let mut sm = self.m.identity();
+        loop {
+            while l & 1 == 0 { l >>= 1; }
+            let res = self.m.op(&sm, &self.seg[l]);
+            if !pred(&res) {
+                while l < self.size {
+                    l <<= 1;
+                    let res = self.m.op(&sm, &self.seg[l]);
+                    if pred(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = res;
+            l += 1;
+            if l.is_power_of_two() { break }
+        }
+        self.n
+    }
+
+    /// Minimum `l` such that `pred(query(l, r))` holds, assuming `pred` is monotone
+    /// and `pred(identity)` is true. Runs in `O(log n)`.
+    pub fn min_left<P>(&self, r: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(r <= self.n);
+        assert!(pred(&self.m.identity()));
+        if r == 0 { return 0 }
+        let mut r = r + self.size;
+        let mut sm = self.m.identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 { r >>= 1; }
+            let res = self.m.op(&self.seg[r], &sm);
+            if !pred(&res) {
+                while r < self.size {
+                    r = 2 * r + 1;
+                    let res = self.m.op(&self.seg[r], &sm);
+                    if pred(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = res;
+            if r.is_power_of_two() { break }
+        }
+        0
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::num::Wrapping;
 
     #[test]
     fn test_small_segtree_sum() {
-        let mut seg = SegTree::new(
-            10, |&a, &b| a + b, 0
-        );
+        let mut seg = SegTree::new(10, Sum::new());
         for i in 0..10 {
             seg.update(i, i);
         }
@@ -100,4 +193,74 @@ mod tests {
         assert_eq!(seg.query(3, 10), 37);
         assert_eq!(seg.query(4, 6), 9);
     }
+
+    #[test]
+    fn test_segtree_composite() {
+        // Point Set Range Composite (non-commutative): `query(l, r)` applies `v[l]` first
+        type W = Wrapping<u64>;
+        let v: Vec<(W, W)> = (1..=5).map(|i| (Wrapping(2 * i - 1), Wrapping(2 * i))).collect();
+        let mut seg = SegTree::from_vec(v.clone(), Affine::new());
+        let apply = |l: usize, r: usize, x: W, v: &[(W, W)]| {
+            v[l..r].iter().fold(x, |x, f| f.0 * x + f.1)
+        };
+        for l in 0..=5 {
+            for r in l..=5 {
+                let f = seg.query(l, r);
+                assert_eq!(f.0 * Wrapping(3) + f.1, apply(l, r, Wrapping(3), &v));
+            }
+        }
+        seg.update(2, (Wrapping(2), Wrapping(0)));
+        let f = seg.all_prod();
+        let mut v = v;
+        v[2] = (Wrapping(2), Wrapping(0));
+        assert_eq!(f.0 + f.1, apply(0, 5, Wrapping(1), &v));
+    }
+
+    #[test]
+    fn test_segtree_non_copy() {
+        // sorted multiset of the range ("merge sort tree" style)
+        let m = FnMonoid::new(Vec::new(), |a: &Vec<i32>, b: &Vec<i32>| {
+            let mut c = a.clone();
+            c.extend_from_slice(b);
+            c.sort();
+            c
+        });
+        let seg = SegTree::from_vec(vec![vec![5], vec![1], vec![4], vec![2], vec![3]], m);
+        assert_eq!(seg.query(1, 4), vec![1, 2, 4]);
+        assert_eq!(seg.all_prod(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(seg.get(2), &vec![4]);
+        assert_eq!(seg.len(), 5);
+        let seg = SegTree::from_vec(vec![3i64, 1, 4, 1, 5], Min::new());
+        assert_eq!(seg.query(0, 0), i64::MAX);
+        assert_eq!(seg.query(2, 5), 1);
+    }
+
+    #[test]
+    fn test_segtree_max_right_min_left() {
+        let mut rng = rand::thread_rng();
+        for n in 0..40 {
+            let v: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(0..10))).collect();
+            let seg = SegTree::from_vec(v.clone(), Sum::new());
+            for x in 0..60 {
+                for l in 0..=n {
+                    let mut r = l;
+                    let mut s = 0;
+                    while r < n && s + v[r] <= x {
+                        s += v[r];
+                        r += 1;
+                    }
+                    assert_eq!(seg.max_right(l, |&s| s <= x), r);
+                }
+                for r in 0..=n {
+                    let mut l = r;
+                    let mut s = 0;
+                    while l > 0 && s + v[l - 1] <= x {
+                        s += v[l - 1];
+                        l -= 1;
+                    }
+                    assert_eq!(seg.min_left(r, |&s| s <= x), l);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,296 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("segtree_beats")]
+/// ## Segment Tree Beats (Ji Driver segment tree)
+///
+/// Range `chmin` / `chmax` / `add` and range `sum` / `min` / `max` queries on `i64`,
+/// in amortized `O(log^2 n)`. All ranges are half-open `[l, r)`.
+///
+/// Each node keeps the maximum, its count and the strict second maximum
+/// (and the same for the minimum), so a `chmin` that only lowers the maximum
+/// can be applied to a whole node at once.
+pub struct SegTreeBeats {
+    n: usize,
+    size: usize,
+    max_v: Vec<i64>,
+    smax_v: Vec<i64>,
+    max_c: Vec<i64>,
+    min_v: Vec<i64>,
+    smin_v: Vec<i64>,
+    min_c: Vec<i64>,
+    sum: Vec<i64>,
+    len: Vec<i64>,
+    ladd: Vec<i64>,
+}
+
+#[snippet("segtree_beats")]
+impl SegTreeBeats {
+    const INF: i64 = i64::MAX;
+    const NINF: i64 = i64::MIN;
+
+    pub fn new(n: usize) -> Self {
+        Self::from_vec(&vec![0; n])
+    }
+
+    pub fn from_vec(v: &[i64]) -> Self {
+        let n = v.len();
+        let mut size = 1;
+        while size < n { size <<= 1; }
+        let mut seg = Self {
+            n,
+            size,
+            max_v: vec![Self::NINF; 2 * size],
+            smax_v: vec![Self::NINF; 2 * size],
+            max_c: vec![0; 2 * size],
+            min_v: vec![Self::INF; 2 * size],
+            smin_v: vec![Self::INF; 2 * size],
+            min_c: vec![0; 2 * size],
+            sum: vec![0; 2 * size],
+            len: vec![0; 2 * size],
+            ladd: vec![0; 2 * size],
+        };
+        for (i, &x) in v.iter().enumerate() {
+            let k = size + i;
+            seg.max_v[k] = x;
+            seg.min_v[k] = x;
+            seg.max_c[k] = 1;
+            seg.min_c[k] = 1;
+            seg.sum[k] = x;
+            seg.len[k] = 1;
+        }
+        for k in (1..size).rev() {
+            seg.len[k] = seg.len[2 * k] + seg.len[2 * k + 1];
+            seg.recalc(k);
+        }
+        seg
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn recalc(&mut self, k: usize) {
+        let (l, r) = (2 * k, 2 * k + 1);
+        self.sum[k] = self.sum[l] + self.sum[r];
+
+        if self.max_v[l] > self.max_v[r] {
+            self.max_v[k] = self.max_v[l];
+            self.max_c[k] = self.max_c[l];
+            self.smax_v[k] = self.smax_v[l].max(self.max_v[r]);
+        } else if self.max_v[l] < self.max_v[r] {
+            self.max_v[k] = self.max_v[r];
+            self.max_c[k] = self.max_c[r];
+            self.smax_v[k] = self.max_v[l].max(self.smax_v[r]);
+        } else {
+            self.max_v[k] = self.max_v[l];
+            self.max_c[k] = self.max_c[l] + self.max_c[r];
+            self.smax_v[k] = self.smax_v[l].max(self.smax_v[r]);
+        }
+
+        if self.min_v[l] < self.min_v[r] {
+            self.min_v[k] = self.min_v[l];
+            self.min_c[k] = self.min_c[l];
+            self.smin_v[k] = self.smin_v[l].min(self.min_v[r]);
+        } else if self.min_v[l] > self.min_v[r] {
+            self.min_v[k] = self.min_v[r];
+            self.min_c[k] = self.min_c[r];
+            self.smin_v[k] = self.min_v[l].min(self.smin_v[r]);
+        } else {
+            self.min_v[k] = self.min_v[l];
+            self.min_c[k] = self.min_c[l] + self.min_c[r];
+            self.smin_v[k] = self.smin_v[l].min(self.smin_v[r]);
+        }
+    }
+
+    // Lowers the maximum of node k to x (smax_v[k] < x < max_v[k]).
+    fn reflect_chmin(&mut self, k: usize, x: i64) {
+        self.sum[k] += (x - self.max_v[k]) * self.max_c[k];
+        if self.max_v[k] == self.min_v[k] {
+            self.min_v[k] = x;
+        } else if self.max_v[k] == self.smin_v[k] {
+            self.smin_v[k] = x;
+        }
+        self.max_v[k] = x;
+    }
+
+    // Raises the minimum of node k to x (min_v[k] < x < smin_v[k]).
+    fn reflect_chmax(&mut self, k: usize, x: i64) {
+        self.sum[k] += (x - self.min_v[k]) * self.min_c[k];
+        if self.min_v[k] == self.max_v[k] {
+            self.max_v[k] = x;
+        } else if self.min_v[k] == self.smax_v[k] {
+            self.smax_v[k] = x;
+        }
+        self.min_v[k] = x;
+    }
+
+    fn reflect_add(&mut self, k: usize, x: i64) {
+        self.max_v[k] += x;
+        if self.smax_v[k] != Self::NINF { self.smax_v[k] += x; }
+        self.min_v[k] += x;
+        if self.smin_v[k] != Self::INF { self.smin_v[k] += x; }
+        self.sum[k] += self.len[k] * x;
+        self.ladd[k] += x;
+    }
+
+    fn propagate(&mut self, k: usize) {
+        if k >= self.size { return }
+        for c in 2 * k..2 * k + 2 {
+            if self.ladd[k] != 0 {
+                self.reflect_add(c, self.ladd[k]);
+            }
+            if self.max_v[c] > self.max_v[k] {
+                self.reflect_chmin(c, self.max_v[k]);
+            }
+            if self.min_v[c] < self.min_v[k] {
+                self.reflect_chmax(c, self.min_v[k]);
+            }
+        }
+        self.ladd[k] = 0;
+    }
+
+    fn chmin_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a || self.max_v[k] <= x { return }
+        if a <= l && r <= b && self.smax_v[k] < x {
+            self.reflect_chmin(k, x);
+            return;
+        }
+        self.propagate(k);
+        let m = (l + r) / 2;
+        self.chmin_rec(a, b, x, 2 * k, l, m);
+        self.chmin_rec(a, b, x, 2 * k + 1, m, r);
+        self.recalc(k);
+    }
+
+    fn chmax_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a || self.min_v[k] >= x { return }
+        if a <= l && r <= b && self.smin_v[k] > x {
+            self.reflect_chmax(k, x);
+            return;
+        }
+        self.propagate(k);
+        let m = (l + r) / 2;
+        self.chmax_rec(a, b, x, 2 * k, l, m);
+        self.chmax_rec(a, b, x, 2 * k + 1, m, r);
+        self.recalc(k);
+    }
+
+    fn add_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a { return }
+        if a <= l && r <= b {
+            self.reflect_add(k, x);
+            return;
+        }
+        self.propagate(k);
+        let m = (l + r) / 2;
+        self.add_rec(a, b, x, 2 * k, l, m);
+        self.add_rec(a, b, x, 2 * k + 1, m, r);
+        self.recalc(k);
+    }
+
+    // Folds (sum, min, max) over [a, b).
+    fn query_rec(&mut self, a: usize, b: usize, k: usize, l: usize, r: usize) -> (i64, i64, i64) {
+        if b <= l || r <= a { return (0, Self::INF, Self::NINF) }
+        if a <= l && r <= b { return (self.sum[k], self.min_v[k], self.max_v[k]) }
+        self.propagate(k);
+        let m = (l + r) / 2;
+        let (s1, mn1, mx1) = self.query_rec(a, b, 2 * k, l, m);
+        let (s2, mn2, mx2) = self.query_rec(a, b, 2 * k + 1, m, r);
+        (s1 + s2, mn1.min(mn2), mx1.max(mx2))
+    }
+
+    /// `a[i] = min(a[i], x)` for `l <= i < r`
+    pub fn range_chmin(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r <= self.n);
+        self.chmin_rec(l, r, x, 1, 0, self.size);
+    }
+
+    /// `a[i] = max(a[i], x)` for `l <= i < r`
+    pub fn range_chmax(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r <= self.n);
+        self.chmax_rec(l, r, x, 1, 0, self.size);
+    }
+
+    /// `a[i] += x` for `l <= i < r`
+    pub fn range_add(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r <= self.n);
+        self.add_rec(l, r, x, 1, 0, self.size);
+    }
+
+    pub fn range_sum(&mut self, l: usize, r: usize) -> i64 {
+        assert!(l <= r && r <= self.n);
+        self.query_rec(l, r, 1, 0, self.size).0
+    }
+
+    /// `i64::MAX` for an empty range.
+    pub fn range_min(&mut self, l: usize, r: usize) -> i64 {
+        assert!(l <= r && r <= self.n);
+        self.query_rec(l, r, 1, 0, self.size).1
+    }
+
+    /// `i64::MIN` for an empty range.
+    pub fn range_max(&mut self, l: usize, r: usize) -> i64 {
+        assert!(l <= r && r <= self.n);
+        self.query_rec(l, r, 1, 0, self.size).2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_beats_small() {
+        let mut seg = SegTreeBeats::from_vec(&[5, 1, 4, 2, 3]);
+        seg.range_chmin(0, 5, 3);
+        // 3 1 3 2 3
+        assert_eq!(seg.range_sum(0, 5), 12);
+        seg.range_chmax(1, 4, 2);
+        // 3 2 3 2 3
+        assert_eq!(seg.range_min(0, 5), 2);
+        seg.range_add(2, 5, -4);
+        // 3 2 -1 -2 -1
+        assert_eq!(seg.range_sum(0, 5), 1);
+        assert_eq!(seg.range_max(2, 5), -1);
+        assert_eq!(seg.range_min(0, 5), -2);
+        assert_eq!(seg.range_sum(3, 3), 0);
+    }
+
+    #[test]
+    fn test_beats_random() {
+        let mut rng = rand::thread_rng();
+        for n in 1..40 {
+            let mut a: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(-100..100))).collect();
+            let mut seg = SegTreeBeats::from_vec(&a);
+            for _ in 0..300 {
+                let l = rng.sample(Uniform::from(0..n));
+                let r = rng.sample(Uniform::from(l + 1..=n));
+                let x = rng.sample(Uniform::from(-100..100));
+                match rng.sample(Uniform::from(0..6)) {
+                    0 => {
+                        seg.range_chmin(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                    }
+                    1 => {
+                        seg.range_chmax(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                    }
+                    2 => {
+                        seg.range_add(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v += x);
+                    }
+                    3 => assert_eq!(seg.range_sum(l, r), a[l..r].iter().sum::<i64>()),
+                    4 => assert_eq!(seg.range_min(l, r), *a[l..r].iter().min().unwrap()),
+                    _ => assert_eq!(seg.range_max(l, r), *a[l..r].iter().max().unwrap()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::{Idempotent, Monoid};
+
+#[snippet("sparse_table", include = "monoid")]
+/// Static range fold for an idempotent monoid (`Min`, `Max`, `Gcd`):
+/// `O(n log n)` to build, `O(1)` per query.
+pub struct SparseTable<M>
+where
+    M: Idempotent,
+{
+    table: Vec<Vec<M::S>>,
+    m: M,
+}
+
+#[snippet("sparse_table", include = "monoid")]
+impl<M> SparseTable<M>
+where
+    M: Idempotent,
+{
+    pub fn from_vec(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
+        let mut table = vec![v];
+        let mut k = 1;
+        while 2 * k <= n {
+            let prev = table.last().unwrap();
+            let row = (0..=n - 2 * k).map(|i| m.op(&prev[i], &prev[i + k])).collect();
+            table.push(row);
+            k *= 2;
+        }
+        Self { table, m }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `a[l] * a[l+1] * ... * a[r-1]`
+    pub fn query(&self, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.len());
+        if l == r { return self.m.identity() }
+        let k = (usize::BITS - 1 - (r - l).leading_zeros()) as usize;
+        self.m.op(&self.table[k][l], &self.table[k][r - (1 << k)])
+    }
+}
+
+#[snippet("disjoint_sparse_table", include = "monoid")]
+/// Static range fold for any monoid: `O(n log n)` to build, `O(1)` per query (one `op`).
+///
+/// At level `h` the array is cut into blocks of `2 << h`; `table[h][i]` is the fold
+/// from `i` to the middle of its block (left half) or from the middle to `i` (right half).
+pub struct DisjointSparseTable<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    data: Vec<M::S>,
+    table: Vec<Vec<M::S>>,
+    m: M,
+}
+
+#[snippet("disjoint_sparse_table", include = "monoid")]
+impl<M> DisjointSparseTable<M>
+where
+    M: Monoid,
+{
+    pub fn from_vec(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
+        let mut size = 1;
+        let mut log = 0;
+        while size < n {
+            size <<= 1;
+            log += 1;
+        }
+        let mut data = v;
+        data.resize(size, m.identity());
+        let mut table = vec![vec![m.identity(); size]; log];
+        for (h, row) in table.iter_mut().enumerate() {
+            let half = 1 << h;
+            for c in (half..size).step_by(2 * half) {
+                row[c - 1] = data[c - 1].clone();
+                for i in (c - half..c - 1).rev() {
+                    row[i] = m.op(&data[i], &row[i + 1]);
+                }
+                row[c] = data[c].clone();
+                for i in c + 1..c + half {
+                    row[i] = m.op(&row[i - 1], &data[i]);
+                }
+            }
+        }
+        Self { n, data, table, m }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// `a[l] * a[l+1] * ... * a[r-1]`
+    pub fn query(&self, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.n);
+        if l == r { return self.m.identity() }
+        let r = r - 1;
+        if l == r { return self.data[l].clone() }
+        let h = (usize::BITS - 1 - (l ^ r).leading_zeros()) as usize;
+        self.m.op(&self.table[h][l], &self.table[h][r])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_sparse_table() {
+        let mut rng = rand::thread_rng();
+        for n in 0..40 {
+            let v: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(1..100))).collect();
+            let min = SparseTable::from_vec(v.clone(), Min::new());
+            let gcd = SparseTable::from_vec(v.clone(), Gcd::new());
+            for l in 0..=n {
+                for r in l..=n {
+                    assert_eq!(min.query(l, r), v[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                    let g = v[l..r].iter().fold(0, |a, &b| crate::math::gcd::gcd(a, b));
+                    assert_eq!(gcd.query(l, r), g);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_disjoint_sparse_table() {
+        let mut rng = rand::thread_rng();
+        // non-commutative
+        let m = Affine::<Wrapping<u64>>::new();
+        for n in 0..40 {
+            let v: Vec<_> = (0..n).map(|_| (Wrapping(rng.gen()), Wrapping(rng.gen()))).collect();
+            let dst = DisjointSparseTable::from_vec(v.clone(), m);
+            let w: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(-100..100))).collect();
+            let sum = DisjointSparseTable::from_vec(w.clone(), Sum::new());
+            for l in 0..=n {
+                for r in l..=n {
+                    let expected = v[l..r].iter().fold(m.identity(), |acc, f| m.op(&acc, f));
+                    assert_eq!(dst.query(l, r), expected);
+                    assert_eq!(sum.query(l, r), w[l..r].iter().sum::<i64>());
+                }
+            }
+        }
+    }
+}
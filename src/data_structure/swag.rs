@@ -0,0 +1,213 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::Monoid;
+
+#[snippet("swag", include = "monoid")]
+/// Queue with the fold of all its elements (sliding window aggregation),
+/// for any monoid; every operation is amortized `O(1)`.
+///
+/// Two stacks: `front` keeps suffix folds (its top is the front of the queue),
+/// `back` keeps the elements pushed since the last transfer and their fold.
+pub struct SlidingWindowAggregation<M>
+where
+    M: Monoid,
+{
+    front: Vec<(M::S, M::S)>,
+    back: Vec<M::S>,
+    back_fold: M::S,
+    m: M,
+}
+
+#[snippet("swag", include = "monoid")]
+impl<M> SlidingWindowAggregation<M>
+where
+    M: Monoid,
+{
+    pub fn new(m: M) -> Self {
+        let back_fold = m.identity();
+        Self { front: vec![], back: vec![], back_fold, m }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, x: M::S) {
+        self.back_fold = self.m.op(&self.back_fold, &x);
+        self.back.push(x);
+    }
+
+    /// Removes the oldest element.
+    pub fn pop(&mut self) -> Option<M::S> {
+        if self.front.is_empty() {
+            while let Some(x) = self.back.pop() {
+                let f = match self.front.last() {
+                    Some((_, f)) => self.m.op(&x, f),
+                    None => x.clone(),
+                };
+                self.front.push((x, f));
+            }
+            self.back_fold = self.m.identity();
+        }
+        self.front.pop().map(|(x, _)| x)
+    }
+
+    /// `a[0] * a[1] * ... * a[len-1]` from the oldest to the newest element.
+    pub fn fold(&self) -> M::S {
+        match self.front.last() {
+            Some((_, f)) => self.m.op(f, &self.back_fold),
+            None => self.back_fold.clone(),
+        }
+    }
+}
+
+#[snippet("swag_deque", include = "monoid")]
+/// Deque with the fold of all its elements, for any monoid; every operation is amortized `O(1)`.
+///
+/// When one side runs out, the other is split in half, so alternating pops stay cheap.
+pub struct DequeAggregation<M>
+where
+    M: Monoid,
+{
+    // (value, fold from this value to the end of `front`); top is the front of the deque
+    front: Vec<(M::S, M::S)>,
+    // (value, fold from the start of `back` to this value); top is the back of the deque
+    back: Vec<(M::S, M::S)>,
+    m: M,
+}
+
+#[snippet("swag_deque", include = "monoid")]
+impl<M> DequeAggregation<M>
+where
+    M: Monoid,
+{
+    pub fn new(m: M) -> Self {
+        Self { front: vec![], back: vec![], m }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push_front(&mut self, x: M::S) {
+        let f = match self.front.last() {
+            Some((_, f)) => self.m.op(&x, f),
+            None => x.clone(),
+        };
+        self.front.push((x, f));
+    }
+
+    pub fn push_back(&mut self, x: M::S) {
+        let f = match self.back.last() {
+            Some((_, f)) => self.m.op(f, &x),
+            None => x.clone(),
+        };
+        self.back.push((x, f));
+    }
+
+    // `items` in deque order; the first `k` of them go to `front`.
+    fn rebuild(&mut self, items: Vec<M::S>, k: usize) {
+        let mut items = items;
+        let rest = items.split_off(k);
+        for x in items.into_iter().rev() {
+            self.push_front(x);
+        }
+        for x in rest {
+            self.push_back(x);
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<M::S> {
+        if self.front.is_empty() {
+            let items: Vec<_> = self.back.drain(..).map(|(x, _)| x).collect();
+            let k = items.len().div_ceil(2);
+            self.rebuild(items, k);
+        }
+        self.front.pop().map(|(x, _)| x)
+    }
+
+    pub fn pop_back(&mut self) -> Option<M::S> {
+        if self.back.is_empty() {
+            let items: Vec<_> = self.front.drain(..).rev().map(|(x, _)| x).collect();
+            let k = items.len() / 2;
+            self.rebuild(items, k);
+        }
+        self.back.pop().map(|(x, _)| x)
+    }
+
+    /// Fold of all elements from the front to the back.
+    pub fn fold(&self) -> M::S {
+        match (self.front.last(), self.back.last()) {
+            (Some((_, f)), Some((_, b))) => self.m.op(f, b),
+            (Some((_, f)), None) => f.clone(),
+            (None, Some((_, b))) => b.clone(),
+            (None, None) => self.m.identity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+    use std::collections::VecDeque;
+    use std::num::Wrapping;
+
+    fn naive<M: Monoid>(m: &M, q: &VecDeque<M::S>) -> M::S {
+        q.iter().fold(m.identity(), |acc, x| m.op(&acc, x))
+    }
+
+    #[test]
+    fn test_swag() {
+        let mut rng = rand::thread_rng();
+        let m = Affine::<Wrapping<u64>>::new();
+        let mut swag = SlidingWindowAggregation::new(m);
+        let mut q = VecDeque::new();
+        for _ in 0..1000 {
+            if rng.sample(Uniform::from(0..3)) == 0 {
+                assert_eq!(swag.pop(), q.pop_front());
+            } else {
+                let x = (Wrapping(rng.gen()), Wrapping(rng.gen()));
+                swag.push(x);
+                q.push_back(x);
+            }
+            assert_eq!(swag.len(), q.len());
+            assert_eq!(swag.fold(), naive(&m, &q));
+        }
+    }
+
+    #[test]
+    fn test_swag_deque() {
+        let mut rng = rand::thread_rng();
+        let m = Affine::<Wrapping<u64>>::new();
+        let mut dq = DequeAggregation::new(m);
+        let mut q = VecDeque::new();
+        for _ in 0..2000 {
+            let x = (Wrapping(rng.gen()), Wrapping(rng.gen()));
+            match rng.sample(Uniform::from(0..4)) {
+                0 => {
+                    dq.push_front(x);
+                    q.push_front(x);
+                }
+                1 => {
+                    dq.push_back(x);
+                    q.push_back(x);
+                }
+                2 => assert_eq!(dq.pop_front(), q.pop_front()),
+                _ => assert_eq!(dq.pop_back(), q.pop_back()),
+            }
+            assert_eq!(dq.len(), q.len());
+            assert_eq!(dq.fold(), naive(&m, &q));
+        }
+    }
+}
@@ -19,13 +19,23 @@ impl UnionFind {
     }
 
     pub fn find(&mut self, x: usize) -> usize {
-        if x == self.parent[x] {
-            x
-        } else {
+        let r = self.leader(x);
+        let mut x = x;
+        while self.parent[x] != r {
             let p = self.parent[x];
-            self.parent[x] = self.find(p);
-            self.parent[x]
+            self.parent[x] = r;
+            x = p;
+        }
+        r
+    }
+
+    /// Same as `find` but without path compression
+    pub fn leader(&self, x: usize) -> usize {
+        let mut x = x;
+        while self.parent[x] != x {
+            x = self.parent[x];
         }
+        x
     }
 
     pub fn unite(&mut self, x: usize, y: usize) -> bool {
@@ -43,11 +53,117 @@ impl UnionFind {
         self.size -= 1;
         true
     }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    /// Size of the component of `x`
+    pub fn size(&mut self, x: usize) -> usize {
+        let r = self.find(x);
+        self.sizes[r]
+    }
+
+    /// Number of components
+    pub fn count(&self) -> usize {
+        self.size
+    }
+
+    /// Members of each component, in increasing order of their smallest member
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.parent.len();
+        let mut index = vec![usize::MAX; n];
+        let mut res: Vec<Vec<usize>> = vec![];
+        for x in 0..n {
+            let r = self.find(x);
+            if index[r] == usize::MAX {
+                index[r] = res.len();
+                res.push(Vec::with_capacity(self.sizes[r]));
+            }
+            res[index[r]].push(x);
+        }
+        res
+    }
+}
+
+#[snippet("persistent_unionfind")]
+/// Partially persistent union-find: union by size, no path compression.
+///
+/// Time `t` means "after the first `t` calls of `unite`"; any past time can be queried
+/// in `O(log n)`, and `when_connected` in `O(log n log q)`.
+pub struct PersistentUnionFind {
+    parent: Vec<usize>,
+    // time when the node stopped being a root (usize::MAX while it is one)
+    since: Vec<usize>,
+    // (time, size) each time the root grew
+    sizes: Vec<Vec<(usize, usize)>>,
+    now: usize,
+}
+
+#[snippet("persistent_unionfind")]
+impl PersistentUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            since: vec![usize::MAX; n],
+            sizes: vec![vec![(0, 1)]; n],
+            now: 0,
+        }
+    }
+
+    /// Number of `unite` calls so far
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    pub fn find(&self, x: usize, t: usize) -> usize {
+        let mut x = x;
+        while self.since[x] <= t {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        self.now += 1;
+        let (px, py) = (self.find(x, self.now), self.find(y, self.now));
+        if px == py { return false }
+        let (sx, sy) = (self.size(px, self.now), self.size(py, self.now));
+        let (large, small) = if sx >= sy { (px, py) } else { (py, px) };
+        self.parent[small] = large;
+        self.since[small] = self.now;
+        self.sizes[large].push((self.now, sx + sy));
+        true
+    }
+
+    pub fn same(&self, x: usize, y: usize, t: usize) -> bool {
+        self.find(x, t) == self.find(y, t)
+    }
+
+    /// Size of the component of `x` at time `t`
+    pub fn size(&self, x: usize, t: usize) -> usize {
+        let h = &self.sizes[self.find(x, t)];
+        h[h.partition_point(|&(s, _)| s <= t) - 1].1
+    }
+
+    /// The first time at which `x` and `y` are connected
+    pub fn when_connected(&self, x: usize, y: usize) -> Option<usize> {
+        if !self.same(x, y, self.now) { return None }
+        if x == y { return Some(0) }
+        let (mut ng, mut ok) = (0, self.now);
+        while ok - ng > 1 {
+            let mid = (ng + ok) / 2;
+            if self.same(x, y, mid) { ok = mid; } else { ng = mid; }
+        }
+        Some(ok)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
 
     #[test]
     fn unionfind_test() {
@@ -59,4 +175,48 @@ mod tests {
         assert!(uf.find(1) != uf.find(3));
         assert_eq!(uf.sizes.iter().max(), Some(&3));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn unionfind_api_test() {
+        let mut uf = UnionFind::new(6);
+        uf.unite(4, 1);
+        uf.unite(2, 5);
+        uf.unite(5, 1);
+        assert!(uf.same(1, 2));
+        assert!(!uf.same(0, 3));
+        assert_eq!(uf.size(4), 4);
+        assert_eq!(uf.size(3), 1);
+        assert_eq!(uf.count(), 3);
+        assert_eq!(uf.leader(2), uf.leader(4));
+        assert_eq!(uf.groups(), vec![vec![0], vec![1, 2, 4, 5], vec![3]]);
+    }
+
+    #[test]
+    fn persistent_unionfind_test() {
+        let mut rng = rand::thread_rng();
+        let n = 20;
+        let mut uf = PersistentUnionFind::new(n);
+        let mut history = vec![UnionFind::new(n)];
+        for _ in 0..40 {
+            let x = rng.sample(Uniform::from(0..n));
+            let y = rng.sample(Uniform::from(0..n));
+            let mut next = UnionFind::new(n);
+            for a in 0..n {
+                let r = history.last_mut().unwrap().find(a);
+                next.unite(a, r);
+            }
+            assert_eq!(uf.unite(x, y), next.unite(x, y));
+            history.push(next);
+        }
+        assert_eq!(uf.now(), 40);
+        for x in 0..n {
+            for y in 0..n {
+                let expected = (0..history.len()).find(|&t| history[t].same(x, y));
+                assert_eq!(uf.when_connected(x, y), expected);
+            }
+            for (t, h) in history.iter_mut().enumerate() {
+                assert_eq!(uf.size(x, t), h.size(x));
+            }
+        }
+    }
+}
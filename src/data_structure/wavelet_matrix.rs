@@ -0,0 +1,325 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("wavelet_matrix")]
+// Bit vector with O(1) rank.
+struct BitVector {
+    blocks: Vec<u64>,
+    acc: Vec<usize>,
+}
+
+#[snippet("wavelet_matrix")]
+impl BitVector {
+    fn new(bits: &[bool]) -> Self {
+        let mut blocks = vec![0u64; bits.len() / 64 + 1];
+        for (i, _) in bits.iter().enumerate().filter(|(_, &b)| b) {
+            blocks[i >> 6] |= 1 << (i & 63);
+        }
+        let mut acc = vec![0; blocks.len() + 1];
+        for (i, b) in blocks.iter().enumerate() {
+            acc[i + 1] = acc[i] + b.count_ones() as usize;
+        }
+        Self { blocks, acc }
+    }
+
+    fn get(&self, i: usize) -> bool {
+        (self.blocks[i >> 6] >> (i & 63)) & 1 == 1
+    }
+
+    // Number of 1s in [0, i)
+    fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i & 63)) - 1;
+        self.acc[i >> 6] + (self.blocks[i >> 6] & mask).count_ones() as usize
+    }
+
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+}
+
+#[snippet("wavelet_matrix")]
+/// Wavelet matrix over `u64` values: static range queries on values in `O(log V)`.
+/// All index ranges are half-open `[l, r)`.
+pub struct WaveletMatrix {
+    n: usize,
+    // number of bits of the values; level `d` looks at bit `log - 1 - d`
+    log: usize,
+    bits: Vec<BitVector>,
+    zeros: Vec<usize>,
+}
+
+#[snippet("wavelet_matrix")]
+impl WaveletMatrix {
+    pub fn new(v: &[u64]) -> Self {
+        let n = v.len();
+        let log = 64 - v.iter().max().map_or(0, |m| m.leading_zeros()) as usize;
+        let mut cur = v.to_vec();
+        let mut bits = Vec::with_capacity(log);
+        let mut zeros = Vec::with_capacity(log);
+        for d in 0..log {
+            let bit = log - 1 - d;
+            let b: Vec<bool> = cur.iter().map(|&x| (x >> bit) & 1 == 1).collect();
+            bits.push(BitVector::new(&b));
+            zeros.push(b.iter().filter(|&&x| !x).count());
+            let (z, o): (Vec<u64>, Vec<u64>) = cur.iter().partition(|&&x| (x >> bit) & 1 == 0);
+            cur = z;
+            cur.extend(o);
+        }
+        Self { n, log, bits, zeros }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn fits(&self, x: u64) -> bool {
+        self.log == 64 || x >> self.log == 0
+    }
+
+    // Moves [l, r) one level down, into the zeros or the ones.
+    fn down(&self, d: usize, l: usize, r: usize, one: bool) -> (usize, usize) {
+        let bv = &self.bits[d];
+        if one {
+            (self.zeros[d] + bv.rank1(l), self.zeros[d] + bv.rank1(r))
+        } else {
+            (bv.rank0(l), bv.rank0(r))
+        }
+    }
+
+    /// `a[i]`
+    pub fn access(&self, i: usize) -> u64 {
+        assert!(i < self.n);
+        let (mut i, mut res) = (i, 0);
+        for d in 0..self.log {
+            let one = self.bits[d].get(i);
+            i = self.down(d, i, i, one).0;
+            if one { res |= 1 << (self.log - 1 - d); }
+        }
+        res
+    }
+
+    /// Number of `i` in `[l, r)` with `a[i] == value`
+    pub fn rank(&self, value: u64, l: usize, r: usize) -> usize {
+        assert!(l <= r && r <= self.n);
+        if !self.fits(value) { return 0 }
+        let (mut l, mut r) = (l, r);
+        for d in 0..self.log {
+            let one = (value >> (self.log - 1 - d)) & 1 == 1;
+            let (nl, nr) = self.down(d, l, r, one);
+            l = nl;
+            r = nr;
+        }
+        r - l
+    }
+
+    /// The `k`-th (0-indexed) smallest value in `a[l..r]`
+    pub fn kth_smallest(&self, l: usize, r: usize, k: usize) -> Option<u64> {
+        assert!(l <= r && r <= self.n);
+        if k >= r - l { return None }
+        let (mut l, mut r, mut k, mut res) = (l, r, k, 0);
+        for d in 0..self.log {
+            let z = self.bits[d].rank0(r) - self.bits[d].rank0(l);
+            let one = k >= z;
+            if one {
+                k -= z;
+                res |= 1 << (self.log - 1 - d);
+            }
+            let (nl, nr) = self.down(d, l, r, one);
+            l = nl;
+            r = nr;
+        }
+        Some(res)
+    }
+
+    /// The `k`-th (0-indexed) largest value in `a[l..r]`
+    pub fn kth_largest(&self, l: usize, r: usize, k: usize) -> Option<u64> {
+        if k >= r - l { return None }
+        self.kth_smallest(l, r, r - l - 1 - k)
+    }
+
+    /// Number of `i` in `[l, r)` with `a[i] < x`
+    pub fn count_less(&self, l: usize, r: usize, x: u64) -> usize {
+        assert!(l <= r && r <= self.n);
+        if !self.fits(x) { return r - l }
+        let (mut l, mut r, mut res) = (l, r, 0);
+        for d in 0..self.log {
+            let one = (x >> (self.log - 1 - d)) & 1 == 1;
+            if one {
+                res += self.bits[d].rank0(r) - self.bits[d].rank0(l);
+            }
+            let (nl, nr) = self.down(d, l, r, one);
+            l = nl;
+            r = nr;
+        }
+        res
+    }
+
+    /// Number of `i` in `[l, r)` with `lo <= a[i] < hi`
+    pub fn range_freq(&self, l: usize, r: usize, lo: u64, hi: u64) -> usize {
+        if lo >= hi { return 0 }
+        self.count_less(l, r, hi) - self.count_less(l, r, lo)
+    }
+
+    /// Largest value `< x` in `a[l..r]`
+    pub fn prev_value(&self, l: usize, r: usize, x: u64) -> Option<u64> {
+        let c = self.count_less(l, r, x);
+        if c == 0 { None } else { self.kth_smallest(l, r, c - 1) }
+    }
+
+    /// Smallest value `>= x` in `a[l..r]`
+    pub fn next_value(&self, l: usize, r: usize, x: u64) -> Option<u64> {
+        self.kth_smallest(l, r, self.count_less(l, r, x))
+    }
+}
+
+#[snippet("wavelet_matrix_sum", include = "wavelet_matrix")]
+/// `WaveletMatrix` that also answers sums of the values in a value range.
+pub struct WaveletMatrixSum {
+    wm: WaveletMatrix,
+    // sums[d][i]: sum of the first i values in the order after level d
+    sums: Vec<Vec<u128>>,
+}
+
+#[snippet("wavelet_matrix_sum", include = "wavelet_matrix")]
+impl WaveletMatrixSum {
+    pub fn new(v: &[u64]) -> Self {
+        let wm = WaveletMatrix::new(v);
+        let mut cur = v.to_vec();
+        let mut sums = Vec::with_capacity(wm.log);
+        for d in 0..wm.log {
+            let bit = wm.log - 1 - d;
+            let (z, o): (Vec<u64>, Vec<u64>) = cur.iter().partition(|&&x| (x >> bit) & 1 == 0);
+            cur = z;
+            cur.extend(o);
+            let mut s = vec![0; cur.len() + 1];
+            for (i, &x) in cur.iter().enumerate() {
+                s[i + 1] = s[i] + x as u128;
+            }
+            sums.push(s);
+        }
+        Self { wm, sums }
+    }
+
+    pub fn wavelet_matrix(&self) -> &WaveletMatrix {
+        &self.wm
+    }
+
+    /// Sum of `a[i]` over `i` in `[l, r)` with `a[i] < x`
+    pub fn sum_less(&self, l: usize, r: usize, x: u64) -> u128 {
+        let wm = &self.wm;
+        assert!(l <= r && r <= wm.n);
+        if wm.log == 0 { return 0 }
+        if !wm.fits(x) { return self.sum_all(l, r) }
+        let (mut l, mut r, mut res) = (l, r, 0);
+        for d in 0..wm.log {
+            let one = (x >> (wm.log - 1 - d)) & 1 == 1;
+            let (zl, zr) = wm.down(d, l, r, false);
+            if one {
+                res += self.sums[d][zr] - self.sums[d][zl];
+            }
+            let (nl, nr) = wm.down(d, l, r, one);
+            l = nl;
+            r = nr;
+        }
+        res
+    }
+
+    // Sum of a[l..r]: [l, r) goes down to both branches of the first level.
+    fn sum_all(&self, l: usize, r: usize) -> u128 {
+        let wm = &self.wm;
+        let (zl, zr) = wm.down(0, l, r, false);
+        let (ol, or) = wm.down(0, l, r, true);
+        self.sums[0][zr] - self.sums[0][zl] + self.sums[0][or] - self.sums[0][ol]
+    }
+
+    /// Sum of `a[i]` over `i` in `[l, r)` with `lo <= a[i] < hi`
+    pub fn range_sum(&self, l: usize, r: usize, lo: u64, hi: u64) -> u128 {
+        if lo >= hi { return 0 }
+        self.sum_less(l, r, hi) - self.sum_less(l, r, lo)
+    }
+
+    /// Sum of the `k` smallest values in `a[l..r]`
+    pub fn sum_smallest(&self, l: usize, r: usize, k: usize) -> u128 {
+        let wm = &self.wm;
+        assert!(l <= r && r <= wm.n && k <= r - l);
+        let (mut l, mut r, mut k, mut res, mut value) = (l, r, k, 0, 0u64);
+        for d in 0..wm.log {
+            let (zl, zr) = wm.down(d, l, r, false);
+            let z = zr - zl;
+            let one = k >= z;
+            if one {
+                k -= z;
+                res += self.sums[d][zr] - self.sums[d][zl];
+                value |= 1 << (wm.log - 1 - d);
+            }
+            let (nl, nr) = wm.down(d, l, r, one);
+            l = nl;
+            r = nr;
+        }
+        res + value as u128 * k as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_wavelet_matrix_random() {
+        let mut rng = rand::thread_rng();
+        for &max in &[1u64, 2, 100, 1 << 40, u64::MAX] {
+            let n = 40;
+            let v: Vec<u64> = (0..n).map(|_| rng.sample(Uniform::from(0..=max))).collect();
+            let wm = WaveletMatrix::new(&v);
+            let ws = WaveletMatrixSum::new(&v);
+            for (i, &x) in v.iter().enumerate() {
+                assert_eq!(wm.access(i), x);
+            }
+            for _ in 0..300 {
+                let l = rng.sample(Uniform::from(0..=n));
+                let r = rng.sample(Uniform::from(l..=n));
+                let mut s = v[l..r].to_vec();
+                s.sort();
+                let x = if rng.sample(Uniform::from(0..2)) == 0 && !v.is_empty() {
+                    v[rng.sample(Uniform::from(0..n))]
+                } else {
+                    rng.sample(Uniform::from(0..=max))
+                };
+                let y = rng.sample(Uniform::from(0..=max));
+                let (lo, hi) = (x.min(y), x.max(y));
+                let k = rng.sample(Uniform::from(0..=s.len()));
+
+                assert_eq!(wm.rank(x, l, r), s.iter().filter(|&&a| a == x).count());
+                assert_eq!(wm.kth_smallest(l, r, k), s.get(k).copied());
+                assert_eq!(wm.kth_largest(l, r, k), s.iter().rev().nth(k).copied());
+                assert_eq!(wm.count_less(l, r, x), s.iter().filter(|&&a| a < x).count());
+                assert_eq!(wm.range_freq(l, r, lo, hi), s.iter().filter(|&&a| lo <= a && a < hi).count());
+                assert_eq!(wm.prev_value(l, r, x), s.iter().rev().find(|&&a| a < x).copied());
+                assert_eq!(wm.next_value(l, r, x), s.iter().find(|&&a| a >= x).copied());
+
+                let sum = |it: &mut dyn Iterator<Item = &u64>| it.map(|&a| a as u128).sum::<u128>();
+                assert_eq!(ws.sum_less(l, r, x), sum(&mut s.iter().filter(|&&a| a < x)));
+                assert_eq!(ws.range_sum(l, r, lo, hi), sum(&mut s.iter().filter(|&&a| lo <= a && a < hi)));
+                assert_eq!(ws.sum_smallest(l, r, k), sum(&mut s.iter().take(k)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wavelet_matrix_zeros() {
+        let wm = WaveletMatrix::new(&[0, 0, 0]);
+        assert_eq!(wm.access(1), 0);
+        assert_eq!(wm.rank(0, 0, 3), 3);
+        assert_eq!(wm.rank(1, 0, 3), 0);
+        assert_eq!(wm.kth_smallest(0, 3, 2), Some(0));
+        assert_eq!(wm.next_value(0, 3, 1), None);
+        let ws = WaveletMatrixSum::new(&[0, 0, 0]);
+        assert_eq!(ws.sum_less(0, 3, 5), 0);
+    }
+}
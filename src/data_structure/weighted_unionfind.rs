@@ -0,0 +1,152 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::data_structure::monoid::Group;
+
+#[snippet("weighted_unionfind", include = "monoid")]
+/// Union-find with potentials over an abelian group (`Sum`, `Xor`, `Sum<ModInt>`, ...).
+///
+/// `unite(x, y, w)` records `pot[y] - pot[x] = w`.
+pub struct WeightedUnionFind<G>
+where
+    G: Group,
+{
+    parent: Vec<usize>,
+    sizes: Vec<usize>,
+    // pot[x] - pot[parent[x]]
+    diff: Vec<G::S>,
+    size: usize,
+    g: G,
+}
+
+#[snippet("weighted_unionfind", include = "monoid")]
+impl<G> WeightedUnionFind<G>
+where
+    G: Group,
+    G::S: PartialEq,
+{
+    pub fn new(n: usize, g: G) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            sizes: vec![1; n],
+            diff: vec![g.identity(); n],
+            size: n,
+            g,
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let p = self.parent[x];
+        if p == x { return x }
+        let r = self.find(p);
+        self.diff[x] = self.g.op(&self.diff[x], &self.diff[p]);
+        self.parent[x] = r;
+        r
+    }
+
+    // pot[x] - pot[find(x)]
+    fn weight(&mut self, x: usize) -> G::S {
+        self.find(x);
+        self.diff[x].clone()
+    }
+
+    /// Records `pot[y] - pot[x] = w`.
+    ///
+    /// Returns `Ok(true)` if two components were merged, `Ok(false)` if `x` and `y` were
+    /// already connected consistently, and `Err(d)` with the known `pot[y] - pot[x] = d`
+    /// if `w` contradicts it (nothing is changed then).
+    pub fn unite(&mut self, x: usize, y: usize, w: G::S) -> Result<bool, G::S> {
+        let (wx, wy) = (self.weight(x), self.weight(y));
+        let (rx, ry) = (self.find(x), self.find(y));
+        if rx == ry {
+            let d = self.g.inv_op(&wy, &wx);
+            return if d == w { Ok(false) } else { Err(d) };
+        }
+        // pot[ry] - pot[rx]
+        let d = self.g.inv_op(&self.g.op(&w, &wx), &wy);
+        if self.sizes[rx] >= self.sizes[ry] {
+            self.parent[ry] = rx;
+            self.diff[ry] = d;
+            self.sizes[rx] += self.sizes[ry];
+        } else {
+            self.parent[rx] = ry;
+            self.diff[rx] = self.g.inv_op(&self.g.identity(), &d);
+            self.sizes[ry] += self.sizes[rx];
+        }
+        self.size -= 1;
+        Ok(true)
+    }
+
+    /// `pot[y] - pot[x]`, or `None` if they are not connected
+    pub fn diff(&mut self, x: usize, y: usize) -> Option<G::S> {
+        if !self.same(x, y) { return None }
+        let (wx, wy) = (self.weight(x), self.weight(y));
+        Some(self.g.inv_op(&wy, &wx))
+    }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let r = self.find(x);
+        self.sizes[r]
+    }
+
+    /// Number of components
+    pub fn count(&self) -> usize {
+        self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::monoid::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_weighted_unionfind_small() {
+        let mut uf = WeightedUnionFind::new(5, Sum::new());
+        assert_eq!(uf.unite(0, 1, 3i64), Ok(true));
+        assert_eq!(uf.unite(1, 2, -5), Ok(true));
+        assert_eq!(uf.diff(0, 2), Some(-2));
+        assert_eq!(uf.diff(2, 0), Some(2));
+        assert_eq!(uf.diff(0, 3), None);
+        assert_eq!(uf.unite(2, 0, 2), Ok(false));
+        assert_eq!(uf.unite(2, 0, 1), Err(2));
+        assert_eq!(uf.size(1), 3);
+        assert_eq!(uf.count(), 3);
+
+        let mut uf = WeightedUnionFind::new(3, Xor::new());
+        assert_eq!(uf.unite(0, 1, 1u8), Ok(true));
+        assert_eq!(uf.unite(1, 2, 1), Ok(true));
+        assert_eq!(uf.diff(0, 2), Some(0));
+        assert_eq!(uf.unite(0, 2, 1), Err(0));
+    }
+
+    #[test]
+    fn test_weighted_unionfind_random() {
+        let mut rng = rand::thread_rng();
+        let n = 50;
+        let pot: Vec<i64> = (0..n).map(|_| rng.sample(Uniform::from(-1000..1000))).collect();
+        let mut uf = WeightedUnionFind::new(n, Sum::new());
+        let mut comp: Vec<usize> = (0..n).collect();
+        for _ in 0..300 {
+            let x = rng.sample(Uniform::from(0..n));
+            let y = rng.sample(Uniform::from(0..n));
+            let connected = comp[x] == comp[y];
+            if rng.sample(Uniform::from(0..2)) == 0 {
+                assert_eq!(uf.unite(x, y, pot[y] - pot[x]), Ok(!connected));
+                let (cx, cy) = (comp[x], comp[y]);
+                comp.iter_mut().filter(|c| **c == cy).for_each(|c| *c = cx);
+                if x != y {
+                    assert!(uf.unite(x, y, pot[y] - pot[x] + 1).is_err());
+                }
+            } else {
+                let expected = if connected { Some(pot[y] - pot[x]) } else { None };
+                assert_eq!(uf.diff(x, y), expected);
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 use cargo_snippet::snippet;
+use crate::math::bigint::bigint;
 
 
 #[snippet("io")]
@@ -28,7 +29,7 @@ pub mod io {
                 buf: BufWriter::new(Box::leak(out).lock()),
             }
         }
-        fn scan_str(&mut self) -> &'static str {
+        pub fn scan_str(&mut self) -> &'static str {
             self.iter.next().unwrap()
         }
         fn scan_raw(&mut self) -> &'static [u8] {
@@ -159,4 +160,32 @@ pub mod io {
             w.print(z);
         }
     }
-}
\ No newline at end of file
+}
+
+#[snippet("io_bigint", include = "io, bigint")]
+impl io::Scan for bigint::BigUint {
+    fn scan(s: &mut io::IO) -> Self {
+        s.scan_str().parse().unwrap()
+    }
+}
+
+#[snippet("io_bigint", include = "io, bigint")]
+impl io::Scan for bigint::BigInt {
+    fn scan(s: &mut io::IO) -> Self {
+        s.scan_str().parse().unwrap()
+    }
+}
+
+#[snippet("io_bigint", include = "io, bigint")]
+impl io::Print for bigint::BigUint {
+    fn print(w: &mut io::IO, x: Self) {
+        w.print(x.to_string());
+    }
+}
+
+#[snippet("io_bigint", include = "io, bigint")]
+impl io::Print for bigint::BigInt {
+    fn print(w: &mut io::IO, x: Self) {
+        w.print(x.to_string());
+    }
+}
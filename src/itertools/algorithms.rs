@@ -12,6 +12,43 @@ fn cumsum<T>(a0: T, list: &[T]) -> Vec<T>
     }).collect::<Vec<T>>()
 }
 
+#[snippet("sliding_window")]
+/// Best element of every window `a[i..i+k]`, where `better(x, y)` means `x` beats `y`;
+/// a monotone deque of indices, `O(n)`.
+fn sliding_window_by<T, F>(a: &[T], k: usize, better: F) -> Vec<T>
+    where T: Copy, F: Fn(&T, &T) -> bool
+{
+    assert!(k >= 1);
+    let mut dq = std::collections::VecDeque::new();
+    let mut res = Vec::with_capacity((a.len() + 1).saturating_sub(k));
+    for (i, x) in a.iter().enumerate() {
+        while let Some(&j) = dq.back() {
+            if better(&a[j], x) { break }
+            dq.pop_back();
+        }
+        dq.push_back(i);
+        if dq[0] + k <= i {
+            dq.pop_front();
+        }
+        if i + 1 >= k {
+            res.push(a[dq[0]]);
+        }
+    }
+    res
+}
+
+#[snippet("sliding_window")]
+/// Minimum of every window `a[i..i+k]`; empty if `k > a.len()`.
+fn sliding_window_min<T: Ord + Copy>(a: &[T], k: usize) -> Vec<T> {
+    sliding_window_by(a, k, |x, y| x < y)
+}
+
+#[snippet("sliding_window")]
+/// Maximum of every window `a[i..i+k]`; empty if `k > a.len()`.
+fn sliding_window_max<T: Ord + Copy>(a: &[T], k: usize) -> Vec<T> {
+    sliding_window_by(a, k, |x, y| x > y)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,4 +74,16 @@ mod tests {
         let cs = cumsum(0, &v);
         assert_eq!(cs, vec![1, 3, 6, 10, 15]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sliding_window() {
+        let a = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        assert_eq!(sliding_window_min(&a, 3), vec![1, 1, 1, 1, 2, 2, 2, 3]);
+        assert_eq!(sliding_window_max(&a, 3), vec![4, 4, 5, 9, 9, 9, 6, 6]);
+        assert_eq!(sliding_window_min(&a, 1), a);
+        assert_eq!(sliding_window_max(&a, 10), vec![9]);
+        assert!(sliding_window_min(&a, 11).is_empty());
+        let empty: Vec<i32> = vec![];
+        assert!(sliding_window_max(&empty, 2).is_empty());
+    }
+}
@@ -2,32 +2,122 @@
 use cargo_snippet::snippet;
 
 #[snippet("pascal")]
-pub fn pascal_triangle(n: usize) -> Vec<Vec<u128>> {
-    let mut c = vec![vec![0u128; n+1]; n+1];
-    c[0][0] = 0;
-    for n in 1..=n {
-        c[n][0] = 1;
-        c[n][n] = 1;
-        for k in 1..n {
-            c[n][k] = c[n-1][k-1] + c[n-1][k];
+/// Binomial table `c[i][k] = iCk` for `0 <= k, i <= n` (zero when `k > i`).
+///
+/// Uses additions only, so any `T` with `Zero + One` works: `u128`, `f64`,
+/// `modint::ModInt` or `mint::MInt<M>` (even for a composite modulus).
+///
+/// `u128` overflows past `n ≈ 130`.
+pub fn pascal_triangle<T>(n: usize) -> Vec<Vec<T>>
+where
+    T: Clone + num::traits::Zero + num::traits::One,
+{
+    let mut c = vec![vec![T::zero(); n+1]; n+1];
+    c[0][0] = T::one();
+    for i in 1..=n {
+        c[i][0] = T::one();
+        for k in 1..=i {
+            c[i][k] = c[i-1][k-1].clone() + c[i-1][k].clone();
         }
     }
     c
 }
 
+#[snippet("pascal_compact")]
+/// Pascal's triangle stored row by row in a single `Vec` of `(n+1)(n+2)/2` elements,
+/// about half the memory of `pascal_triangle`.
+#[derive(Debug, Clone)]
+pub struct PascalTriangle<T> {
+    n: usize,
+    data: Vec<T>,
+}
+
+#[snippet("pascal_compact")]
+impl<T> PascalTriangle<T>
+where
+    T: Clone + num::traits::Zero + num::traits::One,
+{
+    pub fn new(n: usize) -> Self {
+        let mut data: Vec<T> = Vec::with_capacity((n+1) * (n+2) / 2);
+        for i in 0..=n {
+            let prev = data.len() - i.min(data.len());
+            data.push(T::one());
+            for k in 1..i {
+                let v = data[prev+k-1].clone() + data[prev+k].clone();
+                data.push(v);
+            }
+            if i > 0 { data.push(T::one()); }
+        }
+        Self { n, data }
+    }
+
+    /// `iCk`, or zero when `k > i`.
+    pub fn get(&self, i: usize, k: usize) -> T {
+        assert!(i <= self.n);
+        if k > i {
+            T::zero()
+        } else {
+            self.data[i * (i+1) / 2 + k].clone()
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::fps::mint;
+    use crate::math::modint::modint;
 
     #[test]
     fn test_pascal_triangle() {
-        let c = pascal_triangle(100);
+        let c = pascal_triangle::<u128>(100);
+        assert_eq!(c[0][0], 1);
         assert_eq!(c[12][0], 1);
         assert_eq!(c[12][1], 12);
         assert_eq!(c[12][12], 1);
         assert_eq!(c[12][4], 495);
+        assert_eq!(c[12][13], 0);
         assert_eq!(c[91][17], 1_149_101_010_321_489_225);
         assert_eq!(c[100][50], 100_891_344_545_564_193_334_812_497_256);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_pascal_triangle_modint() {
+        modint::set_modint(1_000_000_007i64);
+        let c = pascal_triangle::<modint::ModInt>(1000);
+        assert_eq!(c[1000][500].value(), 159_835_829);
+        // composite modulus: no inverses needed
+        modint::set_modint(12i64);
+        let c = pascal_triangle::<modint::ModInt>(100);
+        let d = pascal_triangle::<u128>(100);
+        for (ci, di) in c.iter().zip(d.iter()) {
+            for (x, y) in ci.iter().zip(di.iter()) {
+                assert_eq!(x.value() as u128, y % 12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pascal_triangle_mint_f64() {
+        type M = mint::MInt<mint::Mod998244353>;
+        let c = pascal_triangle::<M>(300);
+        assert_eq!(c[300][150], M::new(344_126_206));
+        // probability of k heads in 60 fair coin flips
+        let p = pascal_triangle::<f64>(60);
+        let total: f64 = p[60].iter().map(|x| x / 2f64.powi(60)).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+        assert!((p[60][30] / 2f64.powi(60) - 0.10258).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_pascal_compact() {
+        let c = pascal_triangle::<u128>(120);
+        let t = PascalTriangle::<u128>::new(120);
+        for (i, ci) in c.iter().enumerate() {
+            for (k, &x) in ci.iter().enumerate() {
+                assert_eq!(t.get(i, k), x);
+            }
+            assert_eq!(t.get(i, i + 1), 0);
+        }
+    }
+}
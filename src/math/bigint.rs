@@ -0,0 +1,659 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet]
+/// Arbitrary-precision integers for single-file submissions.
+///
+/// Limbs are base `10^9`, little-endian, so decimal parsing and printing are linear.
+/// Multiplication switches from schoolbook to Karatsuba for large operands.
+///
+/// ## Example
+/// ```rust
+/// # use snippets::math::bigint::bigint::*;
+/// let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+/// let b = BigUint::from(987_654_321u64);
+/// assert_eq!((&a * &b).to_string(), "121932631124828532112482853211126352690");
+/// let c = BigInt::from(-7i64) / BigInt::from(2i64);
+/// assert_eq!(c.to_string(), "-3");
+/// ```
+#[allow(clippy::module_inception)]
+pub mod bigint {
+    use std::cmp::Ordering;
+    use std::fmt;
+    use std::ops::*;
+    use std::str::FromStr;
+
+    const BASE: u64 = 1_000_000_000;
+    const WIDTH: usize = 9;
+    const KARATSUBA_THRESHOLD: usize = 48;
+
+    #[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+    pub struct BigUint {
+        // no trailing zero limbs; zero is the empty vector
+        d: Vec<u32>,
+    }
+
+    impl BigUint {
+        pub fn zero() -> Self {
+            Self { d: vec![] }
+        }
+
+        pub fn one() -> Self {
+            Self { d: vec![1] }
+        }
+
+        pub fn is_zero(&self) -> bool {
+            self.d.is_empty()
+        }
+
+        fn from_limbs(mut d: Vec<u32>) -> Self {
+            while d.last() == Some(&0) {
+                d.pop();
+            }
+            Self { d }
+        }
+
+        pub fn to_u128(&self) -> Option<u128> {
+            let mut res: u128 = 0;
+            for &x in self.d.iter().rev() {
+                res = res.checked_mul(BASE as u128)?.checked_add(x as u128)?;
+            }
+            Some(res)
+        }
+
+        pub fn pow(&self, mut e: u64) -> Self {
+            let mut res = Self::one();
+            let mut x = self.clone();
+            while e > 0 {
+                if e & 1 == 1 { res = &res * &x; }
+                e >>= 1;
+                if e > 0 { x = &x * &x; }
+            }
+            res
+        }
+
+        fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+            let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+            let mut res = Vec::with_capacity(a.len() + 1);
+            let mut carry = 0;
+            for (i, &x) in a.iter().enumerate() {
+                let s = x as u64 + b.get(i).map_or(0, |&x| x as u64) + carry;
+                res.push((s % BASE) as u32);
+                carry = s / BASE;
+            }
+            if carry > 0 { res.push(carry as u32); }
+            res
+        }
+
+        // Requires a >= b.
+        fn sub_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+            let mut res = Vec::with_capacity(a.len());
+            let mut borrow = 0;
+            for (i, &x) in a.iter().enumerate() {
+                let mut s = x as i64 - b.get(i).map_or(0, |&x| x as i64) - borrow;
+                borrow = 0;
+                if s < 0 {
+                    s += BASE as i64;
+                    borrow = 1;
+                }
+                res.push(s as u32);
+            }
+            assert_eq!(borrow, 0, "BigUint subtraction underflow");
+            res
+        }
+
+        fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+            if a.is_empty() || b.is_empty() { return vec![] }
+            let mut res = vec![0u32; a.len() + b.len()];
+            for (i, &x) in a.iter().enumerate() {
+                if x == 0 { continue }
+                let mut carry = 0u64;
+                for (j, &y) in b.iter().enumerate() {
+                    let t = res[i + j] as u64 + x as u64 * y as u64 + carry;
+                    res[i + j] = (t % BASE) as u32;
+                    carry = t / BASE;
+                }
+                let mut k = i + b.len();
+                while carry > 0 {
+                    let t = res[k] as u64 + carry;
+                    res[k] = (t % BASE) as u32;
+                    carry = t / BASE;
+                    k += 1;
+                }
+            }
+            res
+        }
+
+        fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+            if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+                return Self::mul_schoolbook(a, b);
+            }
+            // a = a1 * B^m + a0, b = b1 * B^m + b0
+            let m = a.len().max(b.len()) / 2;
+            let split = |x: &[u32]| {
+                let (lo, hi) = x.split_at(m.min(x.len()));
+                (Self::from_limbs(lo.to_vec()).d, hi.to_vec())
+            };
+            let (a0, a1) = split(a);
+            let (b0, b1) = split(b);
+            let z0 = Self::mul_limbs(&a0, &b0);
+            let z2 = Self::mul_limbs(&a1, &b1);
+            let sa = Self::add_limbs(&a0, &a1);
+            let sb = Self::add_limbs(&b0, &b1);
+            let z1 = Self::from_limbs(Self::mul_limbs(&sa, &sb)).d;
+            let z1 = Self::from_limbs(Self::sub_limbs(&z1, &z0)).d;
+            let z1 = Self::from_limbs(Self::sub_limbs(&z1, &z2)).d;
+            let mut res = vec![0u32; a.len() + b.len() + 1];
+            for (shift, z) in [(0, &z0), (m, &z1), (2 * m, &z2)].iter() {
+                let mut carry = 0u64;
+                let mut k = *shift;
+                for &x in z.iter() {
+                    let t = res[k] as u64 + x as u64 + carry;
+                    res[k] = (t % BASE) as u32;
+                    carry = t / BASE;
+                    k += 1;
+                }
+                while carry > 0 {
+                    let t = res[k] as u64 + carry;
+                    res[k] = (t % BASE) as u32;
+                    carry = t / BASE;
+                    k += 1;
+                }
+            }
+            res
+        }
+
+        /// Returns `(self / d, self % d)` for a machine-word divisor.
+        pub fn div_rem_small(&self, d: u32) -> (Self, u32) {
+            assert!(d != 0, "division by zero");
+            let mut q = vec![0u32; self.d.len()];
+            let mut r = 0u64;
+            for i in (0..self.d.len()).rev() {
+                let cur = r * BASE + self.d[i] as u64;
+                q[i] = (cur / d as u64) as u32;
+                r = cur % d as u64;
+            }
+            (Self::from_limbs(q), r as u32)
+        }
+
+        fn mul_small(&self, m: u32) -> Self {
+            let mut res = Vec::with_capacity(self.d.len() + 1);
+            let mut carry = 0u64;
+            for &x in &self.d {
+                let t = x as u64 * m as u64 + carry;
+                res.push((t % BASE) as u32);
+                carry = t / BASE;
+            }
+            if carry > 0 { res.push(carry as u32); }
+            Self::from_limbs(res)
+        }
+
+        /// Returns `(self / other, self % other)` (Knuth's algorithm D).
+        pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+            assert!(!other.is_zero(), "division by zero");
+            if self < other { return (Self::zero(), self.clone()) }
+            if other.d.len() == 1 {
+                let (q, r) = self.div_rem_small(other.d[0]);
+                return (q, Self::from(r as u64));
+            }
+            let norm = (BASE / (*other.d.last().unwrap() as u64 + 1)) as u32;
+            let mut u = self.mul_small(norm).d;
+            let v = other.mul_small(norm).d;
+            let n = v.len();
+            let m = u.len() - n;
+            u.push(0);
+            let mut q = vec![0u32; m + 1];
+            let (vt, vs) = (v[n - 1] as u64, v[n - 2] as u64);
+            for j in (0..=m).rev() {
+                let num = u[j + n] as u64 * BASE + u[j + n - 1] as u64;
+                let mut qhat = num / vt;
+                let mut rhat = num % vt;
+                while qhat >= BASE || qhat * vs > rhat * BASE + u[j + n - 2] as u64 {
+                    qhat -= 1;
+                    rhat += vt;
+                    if rhat >= BASE { break }
+                }
+                let mut borrow = 0i64;
+                let mut carry = 0u64;
+                for i in 0..n {
+                    let p = qhat * v[i] as u64 + carry;
+                    carry = p / BASE;
+                    let mut t = u[i + j] as i64 - (p % BASE) as i64 - borrow;
+                    borrow = 0;
+                    if t < 0 {
+                        t += BASE as i64;
+                        borrow = 1;
+                    }
+                    u[i + j] = t as u32;
+                }
+                let t = u[j + n] as i64 - carry as i64 - borrow;
+                if t < 0 {
+                    // qhat was one too large: add v back
+                    u[j + n] = (t + BASE as i64) as u32;
+                    qhat -= 1;
+                    let mut c = 0u64;
+                    for i in 0..n {
+                        let s = u[i + j] as u64 + v[i] as u64 + c;
+                        u[i + j] = (s % BASE) as u32;
+                        c = s / BASE;
+                    }
+                    u[j + n] = ((u[j + n] as u64 + c) % BASE) as u32;
+                } else {
+                    u[j + n] = t as u32;
+                }
+                q[j] = qhat as u32;
+            }
+            u.truncate(n);
+            let (r, _) = Self::from_limbs(u).div_rem_small(norm);
+            (Self::from_limbs(q), r)
+        }
+    }
+
+    impl From<u64> for BigUint {
+        fn from(mut x: u64) -> Self {
+            let mut d = vec![];
+            while x > 0 {
+                d.push((x % BASE) as u32);
+                x /= BASE;
+            }
+            Self { d }
+        }
+    }
+
+    impl From<u128> for BigUint {
+        fn from(mut x: u128) -> Self {
+            let mut d = vec![];
+            while x > 0 {
+                d.push((x % BASE as u128) as u32);
+                x /= BASE as u128;
+            }
+            Self { d }
+        }
+    }
+
+    impl Ord for BigUint {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.d.len().cmp(&other.d.len()).then_with(|| self.d.iter().rev().cmp(other.d.iter().rev()))
+        }
+    }
+
+    impl PartialOrd for BigUint {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct ParseBigIntError;
+
+    impl FromStr for BigUint {
+        type Err = ParseBigIntError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.as_bytes();
+            if s.is_empty() || !s.iter().all(|c| c.is_ascii_digit()) {
+                return Err(ParseBigIntError);
+            }
+            let mut d = Vec::with_capacity(s.len() / WIDTH + 1);
+            for chunk in s.rchunks(WIDTH) {
+                d.push(chunk.iter().fold(0u32, |acc, &c| acc * 10 + (c - b'0') as u32));
+            }
+            Ok(Self::from_limbs(d))
+        }
+    }
+
+    impl fmt::Display for BigUint {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self.d.last() {
+                None => write!(f, "0"),
+                Some(top) => {
+                    write!(f, "{}", top)?;
+                    for x in self.d.iter().rev().skip(1) {
+                        write!(f, "{:09}", x)?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    impl<'a> Add<&'a BigUint> for &'a BigUint {
+        type Output = BigUint;
+        fn add(self, rhs: Self) -> BigUint {
+            BigUint::from_limbs(BigUint::add_limbs(&self.d, &rhs.d))
+        }
+    }
+
+    impl<'a> Sub<&'a BigUint> for &'a BigUint {
+        type Output = BigUint;
+        fn sub(self, rhs: Self) -> BigUint {
+            assert!(self >= rhs, "BigUint subtraction underflow");
+            BigUint::from_limbs(BigUint::sub_limbs(&self.d, &rhs.d))
+        }
+    }
+
+    impl<'a> Mul<&'a BigUint> for &'a BigUint {
+        type Output = BigUint;
+        fn mul(self, rhs: Self) -> BigUint {
+            BigUint::from_limbs(BigUint::mul_limbs(&self.d, &rhs.d))
+        }
+    }
+
+    impl<'a> Div<&'a BigUint> for &'a BigUint {
+        type Output = BigUint;
+        fn div(self, rhs: Self) -> BigUint {
+            self.div_rem(rhs).0
+        }
+    }
+
+    impl<'a> Rem<&'a BigUint> for &'a BigUint {
+        type Output = BigUint;
+        fn rem(self, rhs: Self) -> BigUint {
+            self.div_rem(rhs).1
+        }
+    }
+
+    #[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+    pub struct BigInt {
+        // zero is never negative
+        neg: bool,
+        mag: BigUint,
+    }
+
+    impl BigInt {
+        fn from_parts(neg: bool, mag: BigUint) -> Self {
+            let neg = neg && !mag.is_zero();
+            Self { neg, mag }
+        }
+
+        pub fn zero() -> Self {
+            Self::default()
+        }
+
+        pub fn is_zero(&self) -> bool {
+            self.mag.is_zero()
+        }
+
+        pub fn is_negative(&self) -> bool {
+            self.neg
+        }
+
+        pub fn abs(&self) -> BigUint {
+            self.mag.clone()
+        }
+
+        pub fn to_i128(&self) -> Option<i128> {
+            let m = self.mag.to_u128()?;
+            if self.neg {
+                if m <= i128::MAX as u128 + 1 { Some((m as i128).wrapping_neg()) } else { None }
+            } else if m <= i128::MAX as u128 {
+                Some(m as i128)
+            } else {
+                None
+            }
+        }
+
+        pub fn pow(&self, e: u64) -> Self {
+            Self::from_parts(self.neg && e % 2 == 1, self.mag.pow(e))
+        }
+
+        /// Truncating division, as for primitive integers.
+        pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+            let (q, r) = self.mag.div_rem(&other.mag);
+            (Self::from_parts(self.neg != other.neg, q), Self::from_parts(self.neg, r))
+        }
+    }
+
+    impl From<BigUint> for BigInt {
+        fn from(mag: BigUint) -> Self {
+            Self { neg: false, mag }
+        }
+    }
+
+    impl From<i64> for BigInt {
+        fn from(x: i64) -> Self {
+            Self::from_parts(x < 0, BigUint::from(x.wrapping_abs() as u64))
+        }
+    }
+
+    impl From<i128> for BigInt {
+        fn from(x: i128) -> Self {
+            Self::from_parts(x < 0, BigUint::from(x.wrapping_abs() as u128))
+        }
+    }
+
+    impl Ord for BigInt {
+        fn cmp(&self, other: &Self) -> Ordering {
+            match (self.neg, other.neg) {
+                (false, false) => self.mag.cmp(&other.mag),
+                (true, true) => other.mag.cmp(&self.mag),
+                (false, true) => Ordering::Greater,
+                (true, false) => Ordering::Less,
+            }
+        }
+    }
+
+    impl PartialOrd for BigInt {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl FromStr for BigInt {
+        type Err = ParseBigIntError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (neg, mag) = match s.as_bytes().first() {
+                Some(b'-') => (true, &s[1..]),
+                Some(b'+') => (false, &s[1..]),
+                _ => (false, s),
+            };
+            Ok(Self::from_parts(neg, mag.parse()?))
+        }
+    }
+
+    impl fmt::Display for BigInt {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            if self.neg { write!(f, "-")?; }
+            write!(f, "{}", self.mag)
+        }
+    }
+
+    impl Neg for BigInt {
+        type Output = BigInt;
+        fn neg(self) -> BigInt {
+            Self::from_parts(!self.neg, self.mag)
+        }
+    }
+
+    impl Neg for &BigInt {
+        type Output = BigInt;
+        fn neg(self) -> BigInt {
+            BigInt::from_parts(!self.neg, self.mag.clone())
+        }
+    }
+
+    impl<'a> Add<&'a BigInt> for &'a BigInt {
+        type Output = BigInt;
+        fn add(self, rhs: Self) -> BigInt {
+            if self.neg == rhs.neg {
+                BigInt::from_parts(self.neg, &self.mag + &rhs.mag)
+            } else if self.mag >= rhs.mag {
+                BigInt::from_parts(self.neg, &self.mag - &rhs.mag)
+            } else {
+                BigInt::from_parts(rhs.neg, &rhs.mag - &self.mag)
+            }
+        }
+    }
+
+    impl<'a> Sub<&'a BigInt> for &'a BigInt {
+        type Output = BigInt;
+        fn sub(self, rhs: Self) -> BigInt {
+            self + &(-rhs)
+        }
+    }
+
+    impl<'a> Mul<&'a BigInt> for &'a BigInt {
+        type Output = BigInt;
+        fn mul(self, rhs: Self) -> BigInt {
+            BigInt::from_parts(self.neg != rhs.neg, &self.mag * &rhs.mag)
+        }
+    }
+
+    impl<'a> Div<&'a BigInt> for &'a BigInt {
+        type Output = BigInt;
+        fn div(self, rhs: Self) -> BigInt {
+            self.div_rem(rhs).0
+        }
+    }
+
+    impl<'a> Rem<&'a BigInt> for &'a BigInt {
+        type Output = BigInt;
+        fn rem(self, rhs: Self) -> BigInt {
+            self.div_rem(rhs).1
+        }
+    }
+
+    macro_rules! forward_binop {
+        ($T: ty, $OpTrait: ident, $op_func: ident, $AssignTrait: ident, $assign_func: ident) => {
+            impl $OpTrait for $T {
+                type Output = $T;
+                fn $op_func(self, rhs: $T) -> $T {
+                    (&self).$op_func(&rhs)
+                }
+            }
+            impl<'a> $OpTrait<&'a $T> for $T {
+                type Output = $T;
+                fn $op_func(self, rhs: &$T) -> $T {
+                    (&self).$op_func(rhs)
+                }
+            }
+            impl $AssignTrait for $T {
+                fn $assign_func(&mut self, rhs: $T) {
+                    *self = (&*self).$op_func(&rhs);
+                }
+            }
+            impl<'a> $AssignTrait<&'a $T> for $T {
+                fn $assign_func(&mut self, rhs: &$T) {
+                    *self = (&*self).$op_func(rhs);
+                }
+            }
+        };
+    }
+
+    forward_binop!(BigUint, Add, add, AddAssign, add_assign);
+    forward_binop!(BigUint, Sub, sub, SubAssign, sub_assign);
+    forward_binop!(BigUint, Mul, mul, MulAssign, mul_assign);
+    forward_binop!(BigUint, Div, div, DivAssign, div_assign);
+    forward_binop!(BigUint, Rem, rem, RemAssign, rem_assign);
+    forward_binop!(BigInt, Add, add, AddAssign, add_assign);
+    forward_binop!(BigInt, Sub, sub, SubAssign, sub_assign);
+    forward_binop!(BigInt, Mul, mul, MulAssign, mul_assign);
+    forward_binop!(BigInt, Div, div, DivAssign, div_assign);
+    forward_binop!(BigInt, Rem, rem, RemAssign, rem_assign);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bigint::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    fn random_biguint<R: Rng>(rng: &mut R, digits: usize) -> BigUint {
+        let mut s = String::with_capacity(digits);
+        s.push((b'1' + rng.sample(Uniform::from(0..9u8))) as char);
+        for _ in 1..digits {
+            s.push((b'0' + rng.sample(Uniform::from(0..10u8))) as char);
+        }
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_biguint_parse_print() {
+        for s in &["0", "1", "999999999", "1000000000", "123456789012345678901234567890"] {
+            assert_eq!(s.parse::<BigUint>().unwrap().to_string(), *s);
+        }
+        assert_eq!("000000000000123".parse::<BigUint>().unwrap().to_string(), "123");
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("".parse::<BigUint>().is_err());
+        assert_eq!("-0".parse::<BigInt>().unwrap().to_string(), "0");
+        assert_eq!("-1000000000000".parse::<BigInt>().unwrap().to_string(), "-1000000000000");
+    }
+
+    #[test]
+    fn test_biguint_vs_u128() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10000 {
+            let a: u64 = rng.sample(Uniform::from(0..u64::MAX));
+            let sft: u32 = rng.sample(Uniform::from(0..63));
+            let b: u64 = rng.sample(Uniform::from(1..u64::MAX >> sft));
+            let (ba, bb) = (BigUint::from(a), BigUint::from(b));
+            let (a, b) = (a as u128, b as u128);
+            assert_eq!((&ba + &bb).to_u128(), Some(a + b));
+            assert_eq!((&ba * &bb).to_u128(), Some(a * b));
+            assert_eq!((&ba / &bb).to_u128(), Some(a / b));
+            assert_eq!((&ba % &bb).to_u128(), Some(a % b));
+            if a >= b {
+                assert_eq!((&ba - &bb).to_u128(), Some(a - b));
+            }
+            assert_eq!(ba.cmp(&bb), a.cmp(&b));
+        }
+        assert_eq!(BigInt::from(i64::MIN).to_string(), i64::MIN.to_string());
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!("+42".parse::<BigInt>().unwrap(), BigInt::from(42i64));
+    }
+
+    #[test]
+    fn test_bigint_vs_i128() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10000 {
+            let a: i64 = rng.sample(Uniform::from(i64::MIN..i64::MAX));
+            let mut b: i64 = rng.sample(Uniform::from(-1_000_000i64..1_000_000));
+            if b == 0 { b = 1; }
+            let (ba, bb) = (BigInt::from(a), BigInt::from(b));
+            let (a, b) = (a as i128, b as i128);
+            assert_eq!((&ba + &bb).to_i128(), Some(a + b));
+            assert_eq!((&ba - &bb).to_i128(), Some(a - b));
+            assert_eq!((&ba * &bb).to_i128(), Some(a * b));
+            assert_eq!((&ba / &bb).to_i128(), Some(a / b));
+            assert_eq!((&ba % &bb).to_i128(), Some(a % b));
+            assert_eq!(ba.cmp(&bb), a.cmp(&b));
+        }
+    }
+
+    #[test]
+    fn test_biguint_large_mul_div() {
+        let mut rng = rand::thread_rng();
+        for &(n, m) in &[(2000, 1500), (900, 30), (500, 499), (60, 10)] {
+            let a = random_biguint(&mut rng, n);
+            let b = random_biguint(&mut rng, m);
+            let c = random_biguint(&mut rng, m - 1);
+            let p = &a * &b + &c;
+            let (q, r) = p.div_rem(&b);
+            assert_eq!(q, a);
+            assert_eq!(r, c);
+            let (q, r) = p.div_rem(&a);
+            assert!(r < a);
+            assert_eq!(q * &a + r, p);
+        }
+    }
+
+    #[test]
+    fn test_bigint_fibonacci_factorial() {
+        let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+        for _ in 0..500 {
+            let c = &a + &b;
+            a = b;
+            b = c;
+        }
+        assert_eq!(
+            a.to_string(),
+            "139423224561697880139724382870407283950070256587697307264108962948325571622863290691557658876222521294125"
+        );
+        let mut f = BigUint::one();
+        for i in 1..=30u64 {
+            f *= BigUint::from(i);
+        }
+        assert_eq!(f.to_string(), "265252859812191058636308480000000");
+        assert_eq!(BigInt::from(-3i64).pow(41).to_string(), "-36472996377170786403");
+        assert_eq!((BigUint::from(10u64).pow(100) - BigUint::one()).to_string(), "9".repeat(100));
+    }
+}
@@ -0,0 +1,167 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::math::quotient_range::quotient_range;
+
+#[snippet("dirichlet", include = "quotient_range")]
+/// ## Du's sieve
+///
+/// Prefix sums of the Möbius function `M(v) = Σ_{i<=v} μ(i)` and
+/// Euler's totient `Φ(v) = Σ_{i<=v} φ(i)` for every `v = n / k` in `O(n^(2/3))`.
+///
+/// Uses `Σ_{i=1}^{v} M(v / i) = 1` and `Σ_{i=1}^{v} Φ(v / i) = v(v+1)/2`.
+/// Values below `n^(2/3)` come from a linear sieve.
+///
+/// `Φ(10^10)` does not fit in `u64`, so totient sums are `i128`.
+///
+/// ## Example
+/// ```rust
+/// # use snippets::math::dirichlet::DirichletPrefixSum;
+/// let d = DirichletPrefixSum::new(1_000_000);
+/// assert_eq!(d.mertens(1_000_000), 212);
+/// assert_eq!(d.totient_sum(1_000_000), 303_963_552_392);
+/// ```
+pub struct DirichletPrefixSum {
+    n: u64,
+    th: u64,
+    small_mu: Vec<i64>,
+    small_phi: Vec<i128>,
+    large_mu: Vec<i64>,
+    large_phi: Vec<i128>,
+}
+
+#[snippet("dirichlet", include = "quotient_range")]
+impl DirichletPrefixSum {
+    pub fn new(n: u64) -> Self {
+        let mut th = (n as f64).powf(2.0 / 3.0) as u64;
+        while th * th < n { th += 1; }
+        let th = th.min(n).max(1);
+        let (small_mu, small_phi) = Self::sieve(th as usize);
+        let kmax = (n / (th + 1)) as usize;
+        let mut large_mu = vec![0; kmax + 1];
+        let mut large_phi = vec![0; kmax + 1];
+        for k in (1..=kmax).rev() {
+            let v = n / k as u64;
+            let mut mu = 1;
+            let mut phi = v as i128 * (v as i128 + 1) / 2;
+            for (l, r, q) in quotient_range(v).skip(1) {
+                let c = (r - l + 1) as i128;
+                let (m, p) = if q <= th {
+                    (small_mu[q as usize], small_phi[q as usize])
+                } else {
+                    let j = k * l as usize;
+                    (large_mu[j], large_phi[j])
+                };
+                mu -= c as i64 * m;
+                phi -= c * p;
+            }
+            large_mu[k] = mu;
+            large_phi[k] = phi;
+        }
+        Self { n, th, small_mu, small_phi, large_mu, large_phi }
+    }
+
+    // Linear sieve for μ and φ, returning their prefix sums on [0, m].
+    fn sieve(m: usize) -> (Vec<i64>, Vec<i128>) {
+        let mut mu = vec![0i64; m + 1];
+        let mut phi = vec![0i128; m + 1];
+        let mut primes = vec![];
+        let mut composite = vec![false; m + 1];
+        mu[1] = 1;
+        phi[1] = 1;
+        for i in 2..=m {
+            if !composite[i] {
+                primes.push(i);
+                mu[i] = -1;
+                phi[i] = (i - 1) as i128;
+            }
+            for &p in &primes {
+                if i * p > m { break }
+                composite[i * p] = true;
+                if i % p == 0 {
+                    mu[i * p] = 0;
+                    phi[i * p] = phi[i] * p as i128;
+                    break;
+                }
+                mu[i * p] = -mu[i];
+                phi[i * p] = phi[i] * (p - 1) as i128;
+            }
+        }
+        for i in 1..=m {
+            mu[i] += mu[i - 1];
+            phi[i] += phi[i - 1];
+        }
+        (mu, phi)
+    }
+
+    /// `Σ_{i<=v} μ(i)`. `v` must be of the form `n / k` or at most `n^(2/3)`.
+    pub fn mertens(&self, v: u64) -> i64 {
+        if v <= self.th {
+            self.small_mu[v as usize]
+        } else {
+            debug_assert_eq!(self.n / (self.n / v), v);
+            self.large_mu[(self.n / v) as usize]
+        }
+    }
+
+    /// `Σ_{i<=v} φ(i)`. `v` must be of the form `n / k` or at most `n^(2/3)`.
+    pub fn totient_sum(&self, v: u64) -> i128 {
+        if v <= self.th {
+            self.small_phi[v as usize]
+        } else {
+            debug_assert_eq!(self.n / (self.n / v), v);
+            self.large_phi[(self.n / v) as usize]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(m: usize) -> (Vec<i64>, Vec<i128>) {
+        let mut mu = vec![0i64; m + 1];
+        let mut phi = vec![0i128; m + 1];
+        for i in 1..=m {
+            let mut x = i;
+            let mut p = 2;
+            let (mut mv, mut pv) = (1, i as i128);
+            while p * p <= x {
+                if x % p == 0 {
+                    let mut e = 0;
+                    while x % p == 0 { x /= p; e += 1; }
+                    mv = if e > 1 { 0 } else { -mv };
+                    pv = pv / p as i128 * (p - 1) as i128;
+                }
+                p += 1;
+            }
+            if x > 1 {
+                mv = -mv;
+                pv = pv / x as i128 * (x - 1) as i128;
+            }
+            mu[i] = mu[i - 1] + mv;
+            phi[i] = phi[i - 1] + pv;
+        }
+        (mu, phi)
+    }
+
+    #[test]
+    fn test_dirichlet_small() {
+        let (mu, phi) = naive(3000);
+        for n in (1..3000).step_by(37) {
+            let d = DirichletPrefixSum::new(n);
+            for (_, _, q) in quotient_range(n) {
+                assert_eq!(d.mertens(q), mu[q as usize]);
+                assert_eq!(d.totient_sum(q), phi[q as usize]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dirichlet_large() {
+        let d = DirichletPrefixSum::new(10_000_000_000);
+        assert_eq!(d.mertens(10_000_000_000), -33_722);
+        assert_eq!(d.totient_sum(10_000_000_000), 30_396_355_092_886_216_366);
+        assert_eq!(d.mertens(1_000_000_000), -222);
+        assert_eq!(d.totient_sum(1_000_000_000), 303_963_551_173_008_414);
+    }
+}
@@ -112,6 +112,22 @@ pub mod mint {
     operator_impl!(Mul, mul, MulAssign, mul_assign);
     operator_impl!(Div, div, DivAssign, div_assign);
 
+    impl<M: Modulo> num::traits::Zero for MInt<M> {
+        fn zero() -> Self {
+            Self::new(0)
+        }
+
+        fn is_zero(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    impl<M: Modulo> num::traits::One for MInt<M> {
+        fn one() -> Self {
+            Self::new(1)
+        }
+    }
+
     impl<M: Modulo> std::iter::Sum for MInt<M> {
         fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
             iter.fold(MInt::new(0), |acc, x| acc + x)
@@ -2,26 +2,64 @@
 use cargo_snippet::snippet;
 
 #[snippet]
-pub fn gcd(a: u64, b: u64) -> u64 {
-    match (a, b) {
-        (a, 0) => a,
-        (a, b) => gcd(b, a % b)
+/// Binary GCD for any primitive integer.
+///
+/// For signed integers the result is non-negative (`T::min_value()` is not supported).
+/// `gcd(0, 0) == 0`.
+pub fn gcd<T: num::traits::PrimInt>(a: T, b: T) -> T {
+    let zero = T::zero();
+    let mut a = if a < zero { zero - a } else { a };
+    let mut b = if b < zero { zero - b } else { b };
+    if a == zero { return b }
+    if b == zero { return a }
+    let k = (a | b).trailing_zeros() as usize;
+    a = a >> a.trailing_zeros() as usize;
+    loop {
+        b = b >> b.trailing_zeros() as usize;
+        if a > b { std::mem::swap(&mut a, &mut b); }
+        b = b - a;
+        if b == zero { return a << k }
     }
 }
 
 #[snippet(include = "gcd")]
-pub fn gcd_list(list: &[u64]) -> u64 {
-    list.iter().fold(list[0], |acc, &x| gcd(x, acc))
+pub fn gcd_list<T: num::traits::PrimInt>(list: &[T]) -> T {
+    list.iter().fold(T::zero(), |acc, &x| gcd(x, acc))
+}
+
+#[snippet(include = "checked_lcm")]
+/// Non-negative lcm; panics on overflow (all builds). Use `checked_lcm` if it may happen.
+pub fn lcm<T: num::traits::PrimInt>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflow")
 }
 
 #[snippet(include = "gcd")]
-pub fn lcm(a: u64, b: u64) -> u64 {
-    a / gcd(a, b) * b
+/// Returns `None` if the lcm does not fit in `T`.
+pub fn checked_lcm<T: num::traits::PrimInt>(a: T, b: T) -> Option<T> {
+    let zero = T::zero();
+    if a == zero || b == zero { return Some(zero) }
+    let a = if a < zero { zero - a } else { a };
+    let b = if b < zero { zero - b } else { b };
+    (a / gcd(a, b)).checked_mul(&b)
 }
 
 #[snippet(include = "lcm")]
-pub fn lcm_list(list: &[u64]) -> u64 {
-    list.iter().fold(1, |acc, &x| lcm(x, acc))
+pub fn lcm_list<T: num::traits::PrimInt>(list: &[T]) -> T {
+    list.iter().fold(T::one(), |acc, &x| lcm(x, acc))
+}
+
+#[snippet(include = "checked_lcm")]
+/// Returns `None` if the lcm (or any partial lcm) does not fit in `T`.
+pub fn checked_lcm_list<T: num::traits::PrimInt>(list: &[T]) -> Option<T> {
+    list.iter().try_fold(T::one(), |acc, &x| checked_lcm(x, acc))
+}
+
+#[snippet(include = "checked_lcm_list")]
+/// Lcm of the list, clamped to `T::max_value()` on overflow.
+///
+/// Useful for "if the lcm exceeds the bound" style problems.
+pub fn saturating_lcm_list<T: num::traits::PrimInt>(list: &[T]) -> T {
+    checked_lcm_list(list).unwrap_or_else(T::max_value)
 }
 
 #[snippet("extgcd")]
@@ -35,6 +73,115 @@ pub fn extgcd(a: i64, b: i64) -> (i64, i64, i64) {
     }
 }
 
+#[snippet("floor_sum")]
+/// Returns `Σ_{i=0}^{n-1} floor((a * i + b) / m)` in `O(log m)`.
+///
+/// `a` and `b` may be negative. Requires `0 <= n <= 2^32`, `1 <= m <= 2^32` and
+/// `|a|, |b| <= 2^32`, so that intermediate values are exact in `i128`;
+/// panics if the result itself does not fit in `i64`.
+///
+/// ## Example
+/// ```rust
+/// # use snippets::math::gcd::floor_sum;
+/// assert_eq!(floor_sum(4, 10, 6, 3), 0 + 0 + 1 + 2);
+/// assert_eq!(floor_sum(3, 2, -1, -1), -1 - 1 - 2);
+/// ```
+pub fn floor_sum(n: i64, m: i64, a: i64, b: i64) -> i64 {
+    const LIM: i64 = 1 << 32;
+    assert!((0..=LIM).contains(&n) && (1..=LIM).contains(&m));
+    assert!((-LIM..=LIM).contains(&a) && (-LIM..=LIM).contains(&b));
+    let (mut n, mut m) = (n as i128, m as i128);
+    let (mut a, mut b) = (a as i128, b as i128);
+    let mut ans = 0;
+    if a < 0 {
+        let a2 = a.rem_euclid(m);
+        ans -= n * (n - 1) / 2 * ((a2 - a) / m);
+        a = a2;
+    }
+    if b < 0 {
+        let b2 = b.rem_euclid(m);
+        ans -= n * ((b2 - b) / m);
+        b = b2;
+    }
+    loop {
+        if a >= m {
+            ans += n * (n - 1) / 2 * (a / m);
+            a %= m;
+        }
+        if b >= m {
+            ans += n * (b / m);
+            b %= m;
+        }
+        let y_max = a * n + b;
+        if y_max < m { break }
+        n = y_max / m;
+        b = y_max % m;
+        std::mem::swap(&mut m, &mut a);
+    }
+    assert!(i64::MIN as i128 <= ans && ans <= i64::MAX as i128, "floor_sum overflow");
+    ans as i64
+}
+
+#[snippet("universal_euclid")]
+/// ## Universal Euclidean algorithm
+///
+/// Walks the line `y(x) = floor((p * x + r) / q)` for `x = 1, 2, ..., n`,
+/// multiplying `u` each time `y` increases by one and `rr` each time `x` does.
+/// That is, the result is the product (under `f`) of
+///
+/// `u^y(1) rr u^(y(2)-y(1)) rr ... u^(y(n)-y(n-1)) rr`
+///
+/// where `y` is counted from `0`. `f` must be associative with identity `id`;
+/// it need not be commutative. Performs `O(log max(p, q))` levels of recursion,
+/// each using `O(log)` multiplications.
+pub struct UniversalEuclid<T, F> {
+    id: T,
+    f: F,
+}
+
+#[snippet("universal_euclid")]
+impl<T, F> UniversalEuclid<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    pub fn new(id: T, f: F) -> Self {
+        Self { id, f }
+    }
+
+    pub fn pow(&self, x: &T, mut e: u64) -> T {
+        let mut res = self.id.clone();
+        let mut x = x.clone();
+        while e > 0 {
+            if e & 1 == 1 { res = (self.f)(&res, &x); }
+            x = (self.f)(&x, &x);
+            e >>= 1;
+        }
+        res
+    }
+
+    pub fn solve(&self, p: u64, q: u64, r: u64, n: u64, u: &T, rr: &T) -> T {
+        assert!(q > 0);
+        let prefix = self.pow(u, r / q);
+        (self.f)(&prefix, &self.solve_rec(p, q, r % q, n, u, rr))
+    }
+
+    // Assumes 0 <= r < q.
+    fn solve_rec(&self, p: u64, q: u64, r: u64, n: u64, u: &T, rr: &T) -> T {
+        if n == 0 { return self.id.clone() }
+        if p >= q {
+            let rr = (self.f)(&self.pow(u, p / q), rr);
+            return self.solve_rec(p % q, q, r, n, u, &rr);
+        }
+        let m = ((p as u128 * n as u128 + r as u128) / q as u128) as u64;
+        if m == 0 { return self.pow(rr, n) }
+        let cnt = n - ((q as u128 * m as u128 - r as u128 - 1) / p as u128) as u64;
+        let head = (self.f)(&self.pow(rr, (q - r - 1) / p), u);
+        let mid = self.solve_rec(q, p, (q - r - 1) % p, m - 1, rr, u);
+        (self.f)(&(self.f)(&head, &mid), &self.pow(rr, cnt))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +230,99 @@ mod tests {
         let (d, x, y) = extgcd(a, b);
         assert_eq!((d, x, y), (29, 8, -17));
     }
+
+    #[test]
+    fn test_floor_sum() {
+        for n in 0..15i64 {
+            for m in 1..12 {
+                for a in -20..20 {
+                    for b in -20..20 {
+                        let naive: i64 = (0..n).map(|i| (a * i + b).div_euclid(m)).sum();
+                        assert_eq!(floor_sum(n, m, a, b), naive);
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            floor_sum(1_000_000_000, 1_000_000_000, 999_999_999, 999_999_999),
+            499_999_999_500_000_000
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "floor_sum overflow")]
+    fn test_floor_sum_overflow() {
+        floor_sum(1 << 32, 1, 1 << 32, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_floor_sum_out_of_range() {
+        floor_sum(1, 1, 1 << 33, 0);
+    }
+
+    #[test]
+    fn test_universal_euclid_floor_sum() {
+        // (number of x, number of y, Σ y)
+        let ue = UniversalEuclid::new((0u64, 0u64, 0u64), |a: &(u64, u64, u64), b: &(u64, u64, u64)| {
+            (a.0 + b.0, a.1 + b.1, a.2 + b.2 + a.1 * b.0)
+        });
+        let u = (0, 1, 0);
+        let rr = (1, 0, 0);
+        for p in 0..15 {
+            for q in 1..15 {
+                for r in 0..30 {
+                    for n in 0..15 {
+                        let naive: u64 = (1..=n).map(|x| (p * x + r) / q).sum();
+                        let (cx, cy, s) = ue.solve(p, q, r, n, &u, &rr);
+                        assert_eq!((cx, cy, s), (n, (p * n + r) / q, naive));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gcd_generic() {
+        assert_eq!(gcd(0u8, 0u8), 0);
+        assert_eq!(gcd(0i32, -7), 7);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(-12i64, -18), 6);
+        assert_eq!(gcd(1u128 << 100, 3u128 << 90), 1 << 90);
+        assert_eq!(gcd(usize::MAX, usize::MAX - 1), 1);
+        for a in 0..200u32 {
+            for b in 0..200u32 {
+                let (mut x, mut y) = (a, b);
+                while y > 0 { let t = x % y; x = y; y = t; }
+                assert_eq!(gcd(a, b), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lcm_signed() {
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(lcm(0i32, 6), 0);
+        assert_eq!(lcm_list(&[-2i64, 3, -5]), 30);
+        assert_eq!(gcd_list::<u32>(&[]), 0);
+    }
+
+    #[test]
+    fn test_checked_lcm() {
+        assert_eq!(checked_lcm(1u64 << 40, 3 << 20), Some(3 << 40));
+        assert_eq!(checked_lcm(1_000_000_007u64, 1_000_000_009u64 * 10_000), None);
+        assert_eq!(checked_lcm(200u8, 3), None);
+        assert_eq!(checked_lcm(100u8, 50), Some(100));
+        let primes = [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        assert_eq!(checked_lcm_list(&primes[..15]), Some(614_889_782_588_491_410));
+        assert_eq!(checked_lcm_list(&primes), None);
+        assert_eq!(saturating_lcm_list(&primes), u64::MAX);
+        assert_eq!(saturating_lcm_list(&[6u64, 10, 15]), 30);
+    }
+
+    #[test]
+    #[should_panic(expected = "lcm overflow")]
+    fn test_lcm_overflow() {
+        lcm(200u8, 3);
+    }
 }
\ No newline at end of file
@@ -5,5 +5,9 @@ pub mod modint;
 pub mod mods;
 pub mod bicoef;
 pub mod fps;
+pub mod quotient_range;
+pub mod dirichlet;
+pub mod rational;
+pub mod bigint;
 
 pub use mods::modulo;
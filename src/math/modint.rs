@@ -91,6 +91,22 @@ pub mod modint {
         }
     }
 
+    impl num::traits::Zero for ModInt {
+        fn zero() -> Self {
+            ModInt(0)
+        }
+
+        fn is_zero(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    impl num::traits::One for ModInt {
+        fn one() -> Self {
+            ModInt::new(1)
+        }
+    }
+
     impl From<ModInt> for Num {
         fn from(m: ModInt) -> Num {
             m.value()
@@ -278,6 +294,15 @@ mod tests {
         assert_eq!(c.value(), 0);
     }
 
+    #[test]
+    fn test_one_mod_1() {
+        use num::traits::One;
+        set_modint(1i64);
+        assert_eq!(ModInt::one().value(), 0);
+        set_modint(1_000_000_007i64);
+        assert_eq!(ModInt::one().value(), 1);
+    }
+
     #[test]
     fn test_pow() {
         set_modint(1_000_000_007i64);
@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+
+#[snippet("quotient_range")]
+/// Iterator over the blocks of `i` in `1..=n` sharing the same value of `n / i`.
+///
+/// Yields `(l, r, q)` such that `n / i == q` holds exactly for `l <= i <= r`
+/// (both ends inclusive). There are `O(n^(1/2))` blocks, in increasing order of `l`.
+///
+/// ## Example
+/// ```rust
+/// # use snippets::math::quotient_range::quotient_range;
+/// let blocks = quotient_range(10).collect::<Vec<_>>();
+/// assert_eq!(blocks, vec![(1, 1, 10), (2, 2, 5), (3, 3, 3), (4, 5, 2), (6, 10, 1)]);
+/// ```
+pub fn quotient_range(n: u64) -> QuotientRange {
+    QuotientRange { n, l: 1 }
+}
+
+#[snippet("quotient_range")]
+#[derive(Debug, Clone)]
+pub struct QuotientRange {
+    n: u64,
+    l: u64,
+}
+
+#[snippet("quotient_range")]
+impl Iterator for QuotientRange {
+    type Item = (u64, u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.l > self.n { return None }
+        let l = self.l;
+        let q = self.n / l;
+        let r = self.n / q;
+        self.l = r + 1;
+        Some((l, r, q))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotient_range_covers_all() {
+        for n in 0..300 {
+            let mut next = 1;
+            for (l, r, q) in quotient_range(n) {
+                assert_eq!(l, next);
+                assert!(l <= r);
+                for i in l..=r {
+                    assert_eq!(n / i, q);
+                }
+                next = r + 1;
+            }
+            assert_eq!(next, n + 1);
+        }
+    }
+
+    #[test]
+    fn test_quotient_range_divisor_sum() {
+        // sum_{i=1}^{n} floor(n / i)
+        let n = 1_000_000_000_000u64;
+        let s: u64 = quotient_range(n).map(|(l, r, q)| (r - l + 1) * q).sum();
+        assert_eq!(s, 27_785_452_449_086);
+        assert_eq!(quotient_range(n).count(), 1_999_999);
+    }
+}
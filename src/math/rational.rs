@@ -0,0 +1,368 @@
+#![allow(dead_code)]
+use cargo_snippet::snippet;
+use crate::math::gcd::gcd;
+
+#[snippet("rational", include = "gcd")]
+/// Exact rational number `num / den`.
+///
+/// Always kept reduced with `den > 0`. Comparison, addition and subtraction
+/// cross-multiply in `i128`, so they are exact for `T` up to `i64`.
+///
+/// ## Example
+/// ```rust
+/// # use snippets::math::rational::Rational;
+/// let a = Rational::new(1i64, 6);
+/// let b = Rational::new(-3i64, -4);
+/// assert_eq!(a + b, Rational::new(11, 12));
+/// assert!(a < b);
+/// assert_eq!(format!("{}", b / a), "9/2");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rational<T> {
+    num: T,
+    den: T,
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    pub fn new(num: T, den: T) -> Self {
+        assert!(den != T::zero(), "denominator must not be zero");
+        let g = gcd(num, den);
+        let (num, den) = (num / g, den / g);
+        if den < T::zero() {
+            Self { num: -num, den: -den }
+        } else {
+            Self { num, den }
+        }
+    }
+
+    pub fn from_integer(n: T) -> Self {
+        Self { num: n, den: T::one() }
+    }
+
+    pub fn zero() -> Self {
+        Self::from_integer(T::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::from_integer(T::one())
+    }
+
+    pub fn num(&self) -> T {
+        self.num
+    }
+
+    pub fn den(&self) -> T {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == T::one()
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.den, self.num)
+    }
+
+    pub fn abs(&self) -> Self {
+        Self { num: self.num.abs(), den: self.den }
+    }
+
+    pub fn floor(&self) -> T {
+        let q = self.num / self.den;
+        if self.num % self.den != T::zero() && self.num < T::zero() { q - T::one() } else { q }
+    }
+
+    pub fn ceil(&self) -> T {
+        let q = self.num / self.den;
+        if self.num % self.den != T::zero() && self.num > T::zero() { q + T::one() } else { q }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.num.into() as f64 / self.den.into() as f64
+    }
+
+    // `self + sign * rhs`, computed in `i128` before reducing
+    fn add_signed(self, rhs: Self, sign: i128) -> Self {
+        let (n1, d1): (i128, i128) = (self.num.into(), self.den.into());
+        let (n2, d2): (i128, i128) = (rhs.num.into(), rhs.den.into());
+        let g = gcd(d1, d2);
+        let num = n1 * (d2 / g) + sign * n2 * (d1 / g);
+        let den = d1 / g * d2;
+        let h = gcd(num, den);
+        let cast = |x: i128| T::from(x).expect("Rational overflow");
+        Self { num: cast(num / h), den: cast(den / h) }
+    }
+
+    /// The closest rational with denominator at most `max_den`, by continued fractions.
+    pub fn limit_denominator(&self, max_den: T) -> Self {
+        assert!(max_den >= T::one());
+        if self.den <= max_den { return *self }
+        let (n0, d0): (i128, i128) = (self.num.into(), self.den.into());
+        let max_den: i128 = max_den.into();
+        let (mut p0, mut q0, mut p1, mut q1) = (0i128, 1i128, 1i128, 0i128);
+        let (mut n, mut d) = (n0, d0);
+        loop {
+            let a = n.div_euclid(d);
+            let q2 = q0 + a * q1;
+            if q2 > max_den { break }
+            let p2 = p0 + a * p1;
+            p0 = p1; q0 = q1;
+            p1 = p2; q1 = q2;
+            let r = n - a * d;
+            n = d;
+            d = r;
+        }
+        let k = (max_den - q0) / q1;
+        let (p2, q2) = (p0 + k * p1, q0 + k * q1);
+        // |p / q - n0 / d0| = |p * d0 - n0 * q| / (q * d0)
+        let e1 = (p1 * d0 - n0 * q1).abs();
+        let e2 = (p2 * d0 - n0 * q2).abs();
+        let cast = |x: i128| T::from(x).unwrap();
+        if e1 * q2 <= e2 * q1 {
+            Self::new(cast(p1), cast(q1))
+        } else {
+            Self::new(cast(p2), cast(q2))
+        }
+    }
+
+    /// Best rational approximation of `x` with denominator at most `max_den`
+    /// (Stern–Brocot descent using the continued fraction of `x`).
+    pub fn approximate(x: f64, max_den: T) -> Self {
+        assert!(max_den >= T::one() && x.is_finite());
+        let max_den: i128 = max_den.into();
+        let (mut p0, mut q0, mut p1, mut q1) = (0i128, 1i128, 1i128, 0i128);
+        let mut y = x;
+        loop {
+            let a = y.floor();
+            let ai = a as i128;
+            let q2 = q0 + ai * q1;
+            if q2 > max_den {
+                let k = (max_den - q0) / q1;
+                let (p2, q2) = (p0 + k * p1, q0 + k * q1);
+                let e1 = (p1 as f64 / q1 as f64 - x).abs();
+                let e2 = (p2 as f64 / q2 as f64 - x).abs();
+                if e2 < e1 { p1 = p2; q1 = q2; }
+                break;
+            }
+            let p2 = p0 + ai * p1;
+            p0 = p1; q0 = q1;
+            p1 = p2; q1 = q2;
+            let frac = y - a;
+            if frac < 1e-12 { break }
+            y = 1.0 / frac;
+        }
+        Self::new(T::from(p1).unwrap(), T::from(q1).unwrap())
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> From<T> for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    fn from(n: T) -> Self {
+        Self::from_integer(n)
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> Ord for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let l: i128 = self.num.into() * other.den.into();
+        let r: i128 = other.num.into() * self.den.into();
+        l.cmp(&r)
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> PartialOrd for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> std::fmt::Display for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128> + std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.den == T::one() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> std::ops::Neg for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { num: -self.num, den: self.den }
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> std::ops::Add for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.add_signed(rhs, 1)
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> std::ops::Sub for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.add_signed(rhs, -1)
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> std::ops::Mul for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let g1 = gcd(self.num, rhs.den);
+        let g2 = gcd(rhs.num, self.den);
+        Self {
+            num: (self.num / g1) * (rhs.num / g2),
+            den: (self.den / g2) * (rhs.den / g1),
+        }
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+impl<T> std::ops::Div for Rational<T>
+where
+    T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+{
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+#[snippet("rational", include = "gcd")]
+macro_rules! impl_rational_assign {
+    ($AssignTrait: ident, $assign_func: ident, $OpTrait: ident, $op_func: ident) => {
+        impl<T> std::ops::$AssignTrait for Rational<T>
+        where
+            T: num::traits::PrimInt + num::traits::Signed + Into<i128>,
+        {
+            fn $assign_func(&mut self, rhs: Self) {
+                *self = std::ops::$OpTrait::$op_func(*self, rhs);
+            }
+        }
+    };
+}
+
+#[snippet("rational", include = "gcd")]
+impl_rational_assign!(AddAssign, add_assign, Add, add);
+#[snippet("rational", include = "gcd")]
+impl_rational_assign!(SubAssign, sub_assign, Sub, sub);
+#[snippet("rational", include = "gcd")]
+impl_rational_assign!(MulAssign, mul_assign, Mul, mul);
+#[snippet("rational", include = "gcd")]
+impl_rational_assign!(DivAssign, div_assign, Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Q = Rational<i64>;
+
+    #[test]
+    fn test_rational_normalize() {
+        let a = Q::new(6, -4);
+        assert_eq!((a.num(), a.den()), (-3, 2));
+        let b = Q::new(0, -5);
+        assert_eq!((b.num(), b.den()), (0, 1));
+        assert_eq!(Q::new(-8, -12), Q::new(2, 3));
+        assert_eq!(format!("{}", Q::new(10, -5)), "-2");
+        assert_eq!(format!("{}", Q::new(10, -4)), "-5/2");
+    }
+
+    #[test]
+    fn test_rational_arith() {
+        let a = Q::new(1, 3);
+        let b = Q::new(-5, 6);
+        assert_eq!(a + b, Q::new(-1, 2));
+        assert_eq!(a - b, Q::new(7, 6));
+        assert_eq!(a * b, Q::new(-5, 18));
+        assert_eq!(a / b, Q::new(-2, 5));
+        assert_eq!(a * Q::zero(), Q::zero());
+        let mut c = a;
+        c += b;
+        c *= Q::from(4);
+        assert_eq!(c, Q::from(-2));
+        c -= Q::one();
+        c /= Q::new(3, 2);
+        assert_eq!(c, Q::from(-2));
+        // no intermediate overflow when the result fits
+        let big = Q::new(1_000_000_007, 998_244_353);
+        assert_eq!(big * big.recip(), Q::one());
+        let (h, q) = (Q::new(i64::MAX, 2), Q::new(i64::MAX, 4));
+        assert_eq!(h - q, q);
+        assert_eq!(h + h, Q::from(i64::MAX));
+        assert_eq!(Q::from(i64::MIN) - Q::from(-1), Q::from(i64::MIN + 1));
+    }
+
+    #[test]
+    fn test_rational_floor_ceil() {
+        for n in -20..=20 {
+            for d in 1..=7 {
+                let q = Q::new(n, d);
+                assert_eq!(q.floor(), (n as f64 / d as f64).floor() as i64);
+                assert_eq!(q.ceil(), (n as f64 / d as f64).ceil() as i64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rational_ord() {
+        let mut v = vec![Q::new(1, 2), Q::new(-1, 3), Q::new(2, 3), Q::new(1, 3), Q::new(-1, 2)];
+        v.sort();
+        assert_eq!(v, vec![Q::new(-1, 2), Q::new(-1, 3), Q::new(1, 3), Q::new(1, 2), Q::new(2, 3)]);
+        let a = Q::new(i64::MAX - 1, i64::MAX);
+        let b = Q::new(i64::MAX - 2, i64::MAX - 1);
+        assert!(b < a);
+    }
+
+    #[test]
+    fn test_rational_approximation() {
+        let pi = Q::new(314_159_265_358_979, 100_000_000_000_000);
+        assert_eq!(pi.limit_denominator(10), Q::new(22, 7));
+        assert_eq!(pi.limit_denominator(100), Q::new(311, 99));
+        assert_eq!(pi.limit_denominator(1000), Q::new(355, 113));
+        assert_eq!(Q::new(-7, 30).limit_denominator(10), Q::new(-2, 9));
+        assert_eq!(Q::new(1, 4).limit_denominator(3), Q::new(1, 3));
+        assert_eq!(Q::approximate(std::f64::consts::PI, 1000), Q::new(355, 113));
+        assert_eq!(Q::approximate(std::f64::consts::PI, 100), Q::new(311, 99));
+        assert_eq!(Q::approximate(-0.75, 100), Q::new(-3, 4));
+        assert_eq!(Q::approximate(2.0f64.sqrt(), 100), Q::new(140, 99));
+    }
+}